@@ -8,7 +8,7 @@ fn main() {
         println!("Column: {col}");
     }
 
-    while let Some(row) = query.fetch_row() {
+    while let Some(row) = query.fetch_row().unwrap() {
         dbg!(row);
     }
 }
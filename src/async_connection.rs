@@ -0,0 +1,333 @@
+use crate::*;
+use futures_core::Stream;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc as std_mpsc;
+use std::task::{Context, Poll};
+use tokio::sync::{mpsc, oneshot};
+
+type Job = Box<dyn FnOnce(&mut Connection) + Send>;
+
+/// An async handle to a [`Connection`] running on a dedicated worker thread.
+///
+/// Every call is sent to the worker as a job and runs to completion there, so
+/// dropping a future (e.g. on timeout) never leaves the connection half way
+/// through a statement or transaction; the result is simply discarded.
+/// A job that panics fails with [`Error::Panic`] and leaves the worker running.
+/// Cloned handles share the same connection and worker, which exits once the
+/// last handle is dropped.
+///
+/// ```no_run
+/// #[derive(serde::Deserialize)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn run() -> crossdb::Result<()> {
+/// let conn = crossdb::AsyncConnection::open_with_memory().await?;
+/// conn.execute("CREATE TABLE IF NOT EXISTS users(id INT, name VARCHAR);").await?;
+/// conn.prepare("INSERT INTO users (id, name) values (?, ?);")
+///     .await?
+///     .execute((1, "Alex"))
+///     .await?;
+/// let users: Vec<User> = conn.query("SELECT * FROM users;").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncConnection {
+    sender: std_mpsc::Sender<Job>,
+}
+
+impl AsyncConnection {
+    pub async fn open(options: OpenOptions) -> Result<Self> {
+        Self::spawn(move || options.open()).await
+    }
+
+    pub async fn open_with_memory() -> Result<Self> {
+        Self::spawn(Connection::open_with_memory).await
+    }
+
+    /// Moves an already opened connection onto a worker thread.
+    pub fn from_connection(conn: Connection) -> Self {
+        let (sender, receiver) = std_mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            let mut conn = conn;
+            while let Ok(job) = receiver.recv() {
+                job(&mut conn);
+            }
+        });
+        Self { sender }
+    }
+
+    async fn spawn<F>(open: F) -> Result<Self>
+    where
+        F: FnOnce() -> Result<Connection> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(open());
+        });
+        let conn = rx.await.map_err(|_| Error::Disconnected)??;
+        Ok(Self::from_connection(conn))
+    }
+
+    /// Runs `f` with exclusive access to the connection on the worker thread.
+    pub async fn call<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job: Job = Box::new(move |conn| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(conn)));
+            let _ = tx.send(result.unwrap_or_else(|payload| Err(panic_error(payload))));
+        });
+        self.sender.send(job).map_err(|_| Error::Disconnected)?;
+        rx.await.map_err(|_| Error::Disconnected)?
+    }
+
+    pub async fn execute<S: Into<String>>(&self, sql: S) -> Result<u64> {
+        let sql = sql.into();
+        self.call(move |conn| conn.execute(sql)).await
+    }
+
+    /// Runs a query and deserializes every row.
+    pub async fn query<T, S>(&self, sql: S) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        S: Into<String>,
+    {
+        let sql = sql.into();
+        self.call(move |conn| conn.query(sql)?.fetch_rows_as())
+            .await
+    }
+
+    /// Streams deserialized rows as the worker fetches them.
+    ///
+    /// The worker reads the whole result in one job and buffers the rows for the
+    /// stream rather than waiting for them to be consumed, so a stream that is not
+    /// polled never holds up other calls on the connection. The engine keeps the
+    /// complete result in memory either way.
+    pub fn query_stream<T, S>(&self, sql: S) -> RowStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+        S: Into<String>,
+    {
+        let sql = sql.into();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let job: Job = Box::new(move |conn| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut query = conn.query(sql)?;
+                while let Some(row) = query.fetch_row_as::<T>() {
+                    // The consumer dropped the stream, stop fetching.
+                    if tx.send(row).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            }));
+            if let Err(err) = result.unwrap_or_else(|payload| Err(panic_error(payload))) {
+                let _ = tx.send(Err(err));
+            }
+        });
+        // If the worker has stopped the sender is dropped with the job and the stream ends.
+        let _ = self.sender.send(job);
+        RowStream { receiver: rx }
+    }
+
+    /// Prepares (and caches) a statement on the worker and returns a handle to it.
+    pub async fn prepare<S: Into<String>>(&self, sql: S) -> Result<AsyncStatement> {
+        let sql = sql.into();
+        let prepared = sql.clone();
+        self.call(move |conn| conn.prepare(prepared).map(|_| ()))
+            .await?;
+        Ok(AsyncStatement {
+            conn: self.clone(),
+            sql,
+        })
+    }
+
+    /// Runs `f` in a transaction, committing on `Ok` and rolling back on `Err`.
+    ///
+    /// The whole closure runs on the worker, so cancelling the future cannot
+    /// leave the transaction open.
+    pub async fn transaction<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.call(move |conn| {
+            let mut tx = conn.transaction()?;
+            let value = f(&mut tx)?;
+            tx.commit()?;
+            Ok(value)
+        })
+        .await
+    }
+}
+
+fn panic_error(payload: Box<dyn Any + Send>) -> Error {
+    let msg = match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "unknown panic".into(),
+        },
+    };
+    Error::Panic(msg)
+}
+
+/// A prepared statement of an [`AsyncConnection`].
+#[derive(Debug, Clone)]
+pub struct AsyncStatement {
+    conn: AsyncConnection,
+    sql: String,
+}
+
+impl AsyncStatement {
+    pub async fn execute<P: IntoParams + Send + 'static>(&self, params: P) -> Result<u64> {
+        let sql = self.sql.clone();
+        self.conn
+            .call(move |conn| conn.prepare(sql)?.execute(params))
+            .await
+    }
+
+    pub async fn query<T, P>(&self, params: P) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        P: IntoParams + Send + 'static,
+    {
+        let sql = self.sql.clone();
+        self.conn
+            .call(move |conn| conn.prepare(sql)?.query(params)?.fetch_rows_as())
+            .await
+    }
+}
+
+/// A [`Stream`] of rows returned by [`AsyncConnection::query_stream`].
+#[derive(Debug)]
+pub struct RowStream<T> {
+    receiver: mpsc::UnboundedReceiver<Result<T>>,
+}
+
+impl<T> Stream for RowStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct User {
+        id: i32,
+        name: String,
+    }
+
+    async fn setup(name: &str) -> AsyncConnection {
+        let conn = AsyncConnection::open(OpenOptions::new().memory(name))
+            .await
+            .unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS users(id INT, name VARCHAR(255));")
+            .await
+            .unwrap();
+        let stmt = conn
+            .prepare("INSERT INTO users (id, name) values (?, ?);")
+            .await
+            .unwrap();
+        stmt.execute((1, "Alex")).await.unwrap();
+        stmt.execute((2, "Thorne")).await.unwrap();
+        conn
+    }
+
+    #[tokio::test]
+    async fn test_async_query() {
+        let conn = setup("async_query").await;
+        let users: Vec<User> = conn.query("SELECT * FROM users;").await.unwrap();
+        assert_eq!(
+            users,
+            vec![
+                User {
+                    id: 1,
+                    name: "Alex".into()
+                },
+                User {
+                    id: 2,
+                    name: "Thorne".into()
+                },
+            ]
+        );
+
+        let result = conn
+            .transaction(|tx| {
+                tx.execute("INSERT INTO users (id, name) values (3, 'Ryder');")?;
+                tx.execute("INSERT INTO missing (id) values (3);")
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(conn.execute("DELETE FROM users;").await.unwrap(), 2);
+
+        let result = conn.prepare("SELECT * FROM missing;").await;
+        assert!(matches!(result, Err(Error::Query(..))));
+    }
+
+    #[tokio::test]
+    async fn test_async_panic() {
+        let conn = setup("async_panic").await;
+        let result = conn
+            .call(|conn| -> Result<()> {
+                conn.execute("DELETE FROM users WHERE id = 1;")?;
+                panic!("boom")
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Panic(msg)) if msg == "boom"));
+        // The worker survives and keeps serving calls.
+        assert_eq!(conn.execute("DELETE FROM users;").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_stream() {
+        let conn = setup("async_stream").await;
+        let mut stream = conn.query_stream::<User, _>("SELECT * FROM users;");
+        let mut ids = vec![];
+        while let Some(user) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            ids.push(user.unwrap().id);
+        }
+        assert_eq!(ids, vec![1, 2]);
+
+        // Dropping a partially consumed stream releases the worker.
+        let stream = conn.query_stream::<User, _>("SELECT * FROM users;");
+        drop(stream);
+        assert_eq!(conn.execute("DELETE FROM users;").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_async_stream_not_polled() {
+        let conn = setup("async_stream_not_polled").await;
+        let stmt = conn
+            .prepare("INSERT INTO users (id, name) values (?, ?);")
+            .await
+            .unwrap();
+        for id in 3..200 {
+            stmt.execute((id, "Ryder")).await.unwrap();
+        }
+
+        // Other calls complete while a large stream is left unconsumed.
+        let mut stream = conn.query_stream::<User, _>("SELECT * FROM users;");
+        assert_eq!(conn.execute("DELETE FROM users;").await.unwrap(), 199);
+        let mut count = 0;
+        while let Some(user) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            user.unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 199);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::*;
+use std::path::Path;
+
+/// Iterator over the results of a multi-statement batch, see [`Connection::execute_batch`].
+///
+/// Each item is the result of one statement; iteration stops after the first error.
+#[derive(Debug)]
+pub struct Batch<'conn> {
+    conn: &'conn Connection,
+    first: *mut xdb_res_t,
+    more: bool,
+}
+
+impl<'conn> Batch<'conn> {
+    pub(crate) fn new(conn: &'conn Connection, first: *mut xdb_res_t) -> Self {
+        Self {
+            conn,
+            first,
+            more: true,
+        }
+    }
+}
+
+impl<'conn> Iterator for Batch<'conn> {
+    type Item = Result<Query<'conn>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.more {
+            return None;
+        }
+        unsafe {
+            let ptr = match std::mem::replace(&mut self.first, std::ptr::null_mut()) {
+                ptr if !ptr.is_null() => ptr,
+                _ => xdb_next_result(self.conn.ptr),
+            };
+            if ptr.is_null() {
+                self.more = false;
+                return None;
+            }
+            self.more = xdb_more_result(ptr);
+            let query = Query::from_res(ptr);
+            if query.is_err() {
+                self.more = false;
+            }
+            Some(query)
+        }
+    }
+}
+
+impl Drop for Batch<'_> {
+    fn drop(&mut self) {
+        // Drain the remaining results so the next query on the connection starts clean.
+        for _ in self.by_ref() {}
+    }
+}
+
+impl Connection {
+    /// Executes several `;`-separated statements, yielding one result per statement.
+    pub fn execute_batch<S: AsRef<str>>(&self, sql: S) -> Result<Batch<'_>> {
+        self.check_read_only(sql.as_ref())?;
+        let sql = CString::new(sql.as_ref())?;
+        let ptr = unsafe { xdb_exec(self.ptr, sql.as_ptr()) };
+        Ok(Batch::new(self, ptr))
+    }
+
+    /// Executes a SQL script statement by statement.
+    ///
+    /// On failure the error is wrapped in [`Error::Script`] with the failing statement
+    /// and the line it starts on. Statements executed before the failure are kept,
+    /// use [`Connection::execute_script_atomic`] to apply all or nothing.
+    pub fn execute_script<S: AsRef<str>>(&self, script: S) -> Result<()> {
+        for (line, statement) in split_statements(script.as_ref()) {
+            self.execute(statement).map_err(|err| Error::Script {
+                line,
+                statement: statement.to_string(),
+                source: Box::new(err),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Executes a SQL script inside a transaction, rolling back on the first failure.
+    pub fn execute_script_atomic<S: AsRef<str>>(&mut self, script: S) -> Result<()> {
+        let tx = self.transaction()?;
+        tx.execute_script(script)?;
+        tx.commit()
+    }
+
+    /// Reads and executes a SQL script file, see [`Connection::execute_script`].
+    pub fn execute_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let script = std::fs::read_to_string(path)?;
+        self.execute_script(script)
+    }
+
+    /// Reads and executes a SQL script file atomically, see [`Connection::execute_script_atomic`].
+    pub fn execute_file_atomic<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let script = std::fs::read_to_string(path)?;
+        self.execute_script_atomic(script)
+    }
+}
+
+/// Splits a script into statements with the 1-based line each one starts on.
+///
+/// Semicolons inside quoted strings, identifiers and comments are ignored.
+pub(crate) fn split_statements(script: &str) -> Vec<(usize, &str)> {
+    let bytes = script.as_bytes();
+    let mut statements = vec![];
+    let mut line = 1;
+    let mut start: Option<(usize, usize)> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'\n' => line += 1,
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    if bytes[i] == b'\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            b';' => {
+                if let Some((begin, begin_line)) = start.take() {
+                    statements.push((begin_line, script[begin..i].trim()));
+                }
+            }
+            _ if b.is_ascii_whitespace() => {}
+            _ => {
+                if start.is_none() {
+                    start = Some((i, line));
+                }
+                if matches!(b, b'\'' | b'"' | b'`') {
+                    i += 1;
+                    while i < bytes.len() {
+                        match bytes[i] {
+                            b'\\' => i += 1,
+                            b'\n' => line += 1,
+                            // A doubled quote is an escaped quote and is skipped as a pair.
+                            q if q == b && bytes.get(i + 1) == Some(&b) => i += 1,
+                            q if q == b => break,
+                            _ => {}
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+    if let Some((begin, begin_line)) = start {
+        statements.push((begin_line, script[begin..].trim()));
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let script = "-- schema; with a comment\n\
+            CREATE TABLE t(id INT, name VARCHAR);\n\
+            \n\
+            INSERT INTO t (id, name) values (1, 'a;b'), (2, 'it''s');\n\
+            /* multi\n   line; */ INSERT INTO t (id, name)\n  values (3, \"c;\");\n\
+            SELECT * FROM t";
+        assert_eq!(
+            split_statements(script),
+            vec![
+                (2, "CREATE TABLE t(id INT, name VARCHAR)"),
+                (
+                    4,
+                    "INSERT INTO t (id, name) values (1, 'a;b'), (2, 'it''s')"
+                ),
+                (6, "INSERT INTO t (id, name)\n  values (3, \"c;\")"),
+                (8, "SELECT * FROM t"),
+            ]
+        );
+        assert!(split_statements(" ;; -- nothing\n").is_empty());
+    }
+
+    #[test]
+    fn test_execute_batch() {
+        let conn = Connection::open_with_memory().unwrap();
+        let results: Vec<_> = conn
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS batch(id INT);\
+                 INSERT INTO batch (id) values (1), (2);\
+                 SELECT * FROM batch;",
+            )
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].affected_rows(), 2);
+        assert_eq!(results[2].row_count(), 2);
+    }
+
+    #[test]
+    fn test_execute_script() {
+        let mut conn = Connection::open_with_memory().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS script(id INT);")
+            .unwrap();
+
+        let err = conn
+            .execute_script_atomic(
+                "INSERT INTO script (id) values (1);\n\
+                 INSERT INTO missing (id) values (2);",
+            )
+            .unwrap_err();
+        match err {
+            Error::Script {
+                line, statement, ..
+            } => {
+                assert_eq!(line, 2);
+                assert_eq!(statement, "INSERT INTO missing (id) values (2)");
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+        let count = conn.query("SELECT * FROM script;").unwrap().row_count();
+        assert_eq!(count, 0);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::*;
-use std::rc::Rc;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std::slice::Iter;
+use std::sync::Arc;
 use strum::{Display, FromRepr, IntoStaticStr};
 
 // https://github.com/crossdb-org/crossdb/blob/main/include/crossdb.h
@@ -54,37 +55,42 @@ pub enum DataType {
 }
 
 impl DataType {
-    unsafe fn from_res(ptr: *mut xdb_res_t, col: u16) -> Self {
+    unsafe fn from_res(ptr: *mut xdb_res_t, col: u16) -> Result<Self, u32> {
         let t = xdb_column_type(ptr, col);
         match Self::from_repr(t) {
-            Some(t) => t,
-            None => unreachable!(),
+            Some(Self::Max) | None => Err(t),
+            Some(t) => Ok(t),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Columns {
-    inner: Rc<Vec<Column>>,
+    inner: Arc<Vec<Column>>,
 }
 
 impl Columns {
-    pub(crate) unsafe fn from_res(ptr: *mut xdb_res_t) -> Self {
+    pub(crate) fn new(columns: Vec<Column>) -> Self {
+        Self {
+            inner: Arc::new(columns),
+        }
+    }
+
+    pub(crate) unsafe fn from_res(ptr: *mut xdb_res_t) -> Result<Self> {
         let count = xdb_column_count(ptr);
         let mut columns = Vec::with_capacity(count as usize);
         for i in 0..(count as u16) {
             unsafe {
                 let name = CStr::from_ptr(xdb_column_name(ptr, i))
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-                let datatype = DataType::from_res(ptr, i);
+                    .to_string_lossy()
+                    .into_owned();
+                let datatype = DataType::from_res(ptr, i).map_err(|t| {
+                    Error::Decode(format!("unknown type code {t} for column '{name}'"))
+                })?;
                 columns.push(Column::new(name, datatype));
             }
         }
-        Self {
-            inner: Rc::new(columns),
-        }
+        Ok(Self::new(columns))
     }
 
     #[allow(clippy::len_without_is_empty)]
@@ -100,14 +106,14 @@ impl Columns {
         self.inner[i].datatype()
     }
 
-    pub fn iter(&self) -> ColumnsIter {
+    pub fn iter(&self) -> ColumnsIter<'_> {
         ColumnsIter {
             inner: self.inner.iter(),
         }
     }
 
     pub fn into_inner(self) -> Option<Vec<Column>> {
-        Rc::into_inner(self.inner)
+        Arc::into_inner(self.inner)
     }
 }
 
@@ -122,6 +128,18 @@ impl<'a> Iterator for ColumnsIter<'a> {
     }
 }
 
+impl Serialize for DataType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.into())
+    }
+}
+
+impl Serialize for Columns {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
 impl<'a> IntoIterator for &'a Columns {
     type Item = &'a Column;
     type IntoIter = ColumnsIter<'a>;
@@ -151,6 +169,16 @@ impl Column {
     }
 }
 
+// `{"name": "id", "type": "INT"}`
+impl Serialize for Column {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut column = serializer.serialize_struct("Column", 2)?;
+        column.serialize_field("name", &self.name)?;
+        column.serialize_field("type", &self.datatype)?;
+        column.end()
+    }
+}
+
 impl Display for Column {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} [{}]", self.name, self.datatype)
@@ -1,50 +1,193 @@
+use crate::timestamp::to_rfc3339;
 use crate::{Row, Value};
 use serde::de::{
-    self, value::Error as DeError, DeserializeSeed, Deserializer, Error, IntoDeserializer,
-    MapAccess, Visitor,
+    self,
+    value::{BorrowedStrDeserializer, Error as DeError, SeqDeserializer},
+    DeserializeSeed, Deserializer, Error, IntoDeserializer, MapAccess, Visitor,
 };
+use serde_json::de::StrRead;
 
-pub(crate) struct RowDeserializer<'de> {
-    row: &'de Row<'de>,
+/// Deserializes a row as a struct or map keyed by column name, a tuple or
+/// sequence of its values, or a single column's value.
+pub(crate) struct RowDeserializer<'r, 'de> {
+    row: &'r Row<'de>,
     index: usize,
+    case_insensitive: bool,
+    fields: &'static [&'static str],
 }
 
-impl<'de> RowDeserializer<'de> {
-    pub(crate) fn new(row: &'de Row<'de>) -> Self {
-        Self { row, index: 0 }
+impl<'r, 'de> RowDeserializer<'r, 'de> {
+    pub(crate) fn new(row: &'r Row<'de>) -> Self {
+        Self {
+            row,
+            index: 0,
+            case_insensitive: false,
+            fields: &[],
+        }
+    }
+
+    /// Matches column names to struct fields ignoring ASCII case.
+    pub(crate) fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    fn single(&self) -> Result<ValueDeserializer<'de>, DeError> {
+        match self.row.values[..] {
+            [value] => Ok(ValueDeserializer(value)),
+            ref values => Err(DeError::custom(format!(
+                "expected a single column, found {}",
+                values.len()
+            ))),
+        }
     }
+
+    fn visit_seq<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        let mut seq = SeqDeserializer::new(self.row.values.iter().copied().map(ValueDeserializer));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
 }
 
-impl<'de> Deserializer<'de> for RowDeserializer<'de> {
+impl<'de> Deserializer<'de> for RowDeserializer<'_, 'de> {
     type Error = DeError;
 
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(DeError::custom("Expects a struct"))
+        visitor.visit_map(self)
     }
 
     fn deserialize_struct<V>(
-        self,
+        mut self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
+        self.fields = fields;
         visitor.visit_map(self)
     }
 
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.visit_seq(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.visit_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.visit_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // `Option<T>` of a single NULL column is `None`, anything else is `Some` and
+    // `T` reads the row as it would without the option, e.g. a one field struct.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.row.values[..] {
+            [Value::Null] => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_identifier
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string option
-        bytes byte_buf unit unit_struct newtype_struct seq tuple
-        tuple_struct map enum identifier ignored_any
+        map
     }
 }
 
-impl<'de> MapAccess<'de> for RowDeserializer<'de> {
+impl<'de> MapAccess<'de> for RowDeserializer<'_, 'de> {
     type Error = DeError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
@@ -54,7 +197,12 @@ impl<'de> MapAccess<'de> for RowDeserializer<'de> {
         if self.index >= self.row.columns.len() {
             return Ok(None);
         }
-        let name = self.row.columns.name(self.index);
+        let mut name = self.row.columns.name(self.index);
+        if self.case_insensitive {
+            if let Some(field) = self.fields.iter().find(|f| f.eq_ignore_ascii_case(name)) {
+                name = field;
+            }
+        }
         seed.deserialize(name.into_deserializer()).map(Some)
     }
 
@@ -65,14 +213,26 @@ impl<'de> MapAccess<'de> for RowDeserializer<'de> {
         if self.index >= self.row.values.len() {
             return Err(de::Error::custom("Value index out of bounds"));
         }
-        let value = &self.row.values[self.index];
+        let value = self.row.values[self.index];
         let result = seed.deserialize(ValueDeserializer(value));
         self.index += 1;
         result
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.values.len() - self.index)
+    }
 }
 
-struct ValueDeserializer<'a>(&'a Value<'a>);
+pub(crate) struct ValueDeserializer<'a>(pub(crate) Value<'a>);
+
+impl<'de> IntoDeserializer<'de, DeError> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
 
 impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
     type Error = DeError;
@@ -81,22 +241,25 @@ impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        match *self.0 {
+        match self.0 {
             Value::Null => visitor.visit_none(),
+            Value::I8(v) => visitor.visit_i8(v),
+            Value::I16(v) => visitor.visit_i16(v),
             Value::I32(v) => visitor.visit_i32(v),
             Value::I64(v) => visitor.visit_i64(v),
+            Value::U8(v) => visitor.visit_u8(v),
+            Value::U16(v) => visitor.visit_u16(v),
             Value::U32(v) => visitor.visit_u32(v),
             Value::U64(v) => visitor.visit_u64(v),
             Value::F32(v) => visitor.visit_f32(v),
             Value::F64(v) => visitor.visit_f64(v),
-            // TODO
             Value::Timestamp(v) => visitor.visit_i64(v),
-            Value::String(v) => visitor.visit_str(v),
-            // TODO: Deserialize Binary to Vec<u8>
-            Value::Binary(_) => todo!(),
+            Value::String(v) => visitor.visit_borrowed_str(v),
+            Value::Binary(v) => visitor.visit_borrowed_bytes(v),
             Value::Bool(v) => visitor.visit_bool(v),
-            Value::Inet(_) => todo!(),
-            Value::Mac(_) => todo!(),
+            Value::Inet(v) => visitor.visit_string(v.to_string()),
+            Value::Mac(v) => visitor.visit_string(v.to_string()),
+            Value::Json(v) => with_json(v, |de| de.deserialize_any(visitor)),
         }
     }
 
@@ -111,19 +274,126 @@ impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        match *self.0 {
+        match self.0 {
             Value::Null => visitor.visit_none(),
+            Value::Json(v) => with_json(v, |de| de.deserialize_option(visitor)),
             _ => visitor.visit_some(self),
         }
     }
 
+    // Date-time types such as `chrono::DateTime<Utc>` and `jiff::Timestamp`
+    // deserialize from strings, so TIMESTAMP is handed to them as RFC 3339.
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Timestamp(v) => visitor.visit_string(to_rfc3339(v)),
+            // A JSON column read as a string gives the document text.
+            Value::Json(v) => visitor.visit_borrowed_str(v),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    // BINARY and MAC read as sequences of bytes for `Vec<u8>` and `[u8; N]`,
+    // `&[u8]` and `serde_bytes` go through `deserialize_bytes` instead.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Binary(v) => visit_byte_seq(v.iter().copied(), visitor),
+            Value::Mac(v) => visit_byte_seq(v.bytes().into_iter(), visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Mac(v) => visitor.visit_bytes(&v.bytes()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Json(v) => with_json(v, |de| de.deserialize_enum(name, variants, visitor)),
+            Value::String(v) => visitor.visit_enum(BorrowedStrDeserializer::new(v)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
     serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit_struct newtype_struct seq tuple
-        tuple_struct map enum identifier ignored_any struct
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        unit_struct tuple_struct map struct identifier ignored_any
     }
 }
 
+fn visit_byte_seq<'de, V, I>(bytes: I, visitor: V) -> Result<V::Value, DeError>
+where
+    V: Visitor<'de>,
+    I: Iterator<Item = u8>,
+{
+    let mut seq = SeqDeserializer::new(bytes);
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+/// Deserializes a JSON column with `f`, rejecting trailing characters.
+fn with_json<'de, T>(
+    json: &'de str,
+    f: impl FnOnce(&mut serde_json::Deserializer<StrRead<'de>>) -> serde_json::Result<T>,
+) -> Result<T, DeError> {
+    let mut de = serde_json::Deserializer::from_str(json);
+    f(&mut de)
+        .and_then(|value| de.end().map(|()| value))
+        .map_err(DeError::custom)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,7 +403,7 @@ mod tests {
     #[test]
     fn test_deserialize_value() {
         fn de<'a, T: Debug + PartialEq + Deserialize<'a>>(v: &'a Value<'a>, expected: T) {
-            let v = ValueDeserializer(v);
+            let v = ValueDeserializer(*v);
             assert_eq!(Deserialize::deserialize(v), Ok(expected));
         }
 
@@ -149,12 +419,339 @@ mod tests {
         de(&Value::I64(1), Some(1_i16));
         de(&Value::I64(1), Some(1_u128));
 
+        de(&Value::I8(-128), -128_i8);
+        de(&Value::I16(-300), -300_i64);
+        de(&Value::U8(255), 255_u8);
+        de(&Value::U16(65535), 65535_i32);
+        assert!(u8::deserialize(ValueDeserializer(Value::I8(-1))).is_err());
+        assert!(i8::deserialize(ValueDeserializer(Value::I16(128))).is_err());
+        assert!(i16::deserialize(ValueDeserializer(Value::U16(40000))).is_err());
+
         de(&Value::String("Hello"), String::from("Hello"));
         de(&Value::String("Hello"), Some(String::from("Hello")));
+
+        de(&Value::Binary(&[]), serde::de::IgnoredAny);
+        de(&Value::Binary(b"Hi"), String::from("Hi"));
+        de(
+            &Value::Inet("10.0.0.1/8".parse().unwrap()),
+            String::from("10.0.0.1/8"),
+        );
+        de(
+            &Value::Mac(mac_address::MacAddress::new([1, 2, 3, 4, 5, 6])),
+            String::from("01:02:03:04:05:06"),
+        );
+    }
+
+    #[test]
+    fn test_deserialize_timestamp() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        let v = Value::Timestamp(1_709_210_096_123_456);
+        let de = || ValueDeserializer(v);
+        assert_eq!(i64::deserialize(de()), Ok(1_709_210_096_123_456));
+        assert_eq!(
+            String::deserialize(de()).unwrap(),
+            "2024-02-29T12:34:56.123456Z"
+        );
+        // `SystemTime` has its own serde form, read it through `crate::timestamp`.
+        assert_eq!(
+            crate::timestamp::deserialize::<SystemTime, _>(de()),
+            Ok(UNIX_EPOCH + Duration::from_micros(1_709_210_096_123_456))
+        );
+        assert!(SystemTime::deserialize(de()).is_err());
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            chrono::DateTime::<chrono::Utc>::deserialize(de()).unwrap(),
+            chrono::DateTime::from_timestamp_micros(1_709_210_096_123_456).unwrap()
+        );
+        #[cfg(feature = "jiff")]
+        assert_eq!(
+            jiff::Timestamp::deserialize(de()).unwrap(),
+            jiff::Timestamp::from_microsecond(1_709_210_096_123_456).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_json() {
+        use std::collections::HashMap;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Kind {
+            Import,
+            Manual { by: String },
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Meta<'a> {
+            source: &'a str,
+            kind: Kind,
+            tags: Vec<String>,
+            extra: Option<HashMap<String, i32>>,
+        }
+
+        let v = Value::Json(r#"{"source":"csv","kind":"Import","tags":["a"],"extra":null}"#);
+        assert_eq!(
+            Meta::deserialize(ValueDeserializer(v)),
+            Ok(Meta {
+                source: "csv",
+                kind: Kind::Import,
+                tags: vec!["a".into()],
+                extra: None,
+            })
+        );
+        assert_eq!(
+            String::deserialize(ValueDeserializer(v)).unwrap(),
+            r#"{"source":"csv","kind":"Import","tags":["a"],"extra":null}"#
+        );
+
+        let v = Value::Json(r#"{"Manual":{"by":"Alex"}}"#);
+        assert_eq!(
+            Kind::deserialize(ValueDeserializer(v)),
+            Ok(Kind::Manual { by: "Alex".into() })
+        );
+        let v = Value::Json("null");
+        assert_eq!(
+            Option::<Vec<i32>>::deserialize(ValueDeserializer(v)),
+            Ok(None)
+        );
+        let v = Value::Json("[1, 2] x");
+        assert!(Vec::<i32>::deserialize(ValueDeserializer(v)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_binary() {
+        let v = Value::Binary(&[0, 1, 255]);
+        let de = || ValueDeserializer(v);
+        assert_eq!(Vec::<u8>::deserialize(de()), Ok(vec![0, 1, 255]));
+        assert_eq!(<&[u8]>::deserialize(de()), Ok(&[0_u8, 1, 255][..]));
+        assert_eq!(<[u8; 3]>::deserialize(de()), Ok([0, 1, 255]));
+        assert!(<[u8; 2]>::deserialize(de()).is_err());
+        assert_eq!(
+            serde_bytes::ByteBuf::deserialize(de()).unwrap().into_vec(),
+            vec![0, 1, 255]
+        );
+        assert_eq!(
+            <&serde_bytes::Bytes>::deserialize(de()).unwrap().as_ref(),
+            &[0, 1, 255]
+        );
+        assert_eq!(
+            Vec::<u8>::deserialize(ValueDeserializer(Value::Binary(&[]))),
+            Ok(vec![])
+        );
+        assert!(String::deserialize(de()).is_err());
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Blob<'a> {
+            #[serde(with = "serde_bytes")]
+            owned: Vec<u8>,
+            #[serde(borrow)]
+            borrowed: &'a [u8],
+        }
+        let row = crate::Row {
+            columns: crate::Columns::new(vec![
+                crate::Column::new("owned".into(), crate::DataType::VBinary),
+                crate::Column::new("borrowed".into(), crate::DataType::VBinary),
+            ]),
+            values: vec![Value::Binary(&[1]), Value::Binary(&[2, 3])],
+        };
+        assert_eq!(
+            Blob::deserialize(RowDeserializer::new(&row)),
+            Ok(Blob {
+                owned: vec![1],
+                borrowed: &[2, 3],
+            })
+        );
+    }
+
+    #[test]
+    fn test_deserialize_inet() {
+        use cidr::IpInet;
+        use std::net::IpAddr;
+
+        let net: IpInet = "192.168.1.10/24".parse().unwrap();
+        let v = Value::Inet(net);
+        assert_eq!(IpInet::deserialize(ValueDeserializer(v)), Ok(net));
+        assert_eq!(
+            String::deserialize(ValueDeserializer(v)),
+            Ok("192.168.1.10/24".into())
+        );
+        // Only host addresses, without a prefix, are plain IP addresses.
+        assert!(IpAddr::deserialize(ValueDeserializer(v)).is_err());
+
+        let host: IpInet = "fe80::1".parse().unwrap();
+        let v = Value::Inet(host);
+        assert_eq!(IpInet::deserialize(ValueDeserializer(v)), Ok(host));
+        assert_eq!(
+            IpAddr::deserialize(ValueDeserializer(v)),
+            Ok("fe80::1".parse().unwrap())
+        );
+        assert_eq!(
+            String::deserialize(ValueDeserializer(v)),
+            Ok("fe80::1".into())
+        );
+        assert_eq!(
+            Option::<IpAddr>::deserialize(ValueDeserializer(Value::Null)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_mac() {
+        use mac_address::MacAddress;
+
+        let mac = MacAddress::new([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0xff]);
+        let v = Value::Mac(mac);
+        assert_eq!(MacAddress::deserialize(ValueDeserializer(v)), Ok(mac));
+        assert_eq!(
+            <[u8; 6]>::deserialize(ValueDeserializer(v)),
+            Ok([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0xff])
+        );
+        assert_eq!(
+            String::deserialize(ValueDeserializer(v)),
+            Ok("00:1A:2B:3C:4D:FF".into())
+        );
+        assert_eq!(
+            serde_bytes::ByteBuf::deserialize(ValueDeserializer(v))
+                .unwrap()
+                .into_vec(),
+            mac.bytes()
+        );
     }
 
     #[test]
     fn test_deserialize_row() {
-        // TODO
+        use crate::{Column, Columns, DataType};
+        use std::collections::HashMap;
+
+        let row = Row {
+            columns: Columns::new(vec![
+                Column::new("ID".into(), DataType::Int),
+                Column::new("user_name".into(), DataType::VChar),
+                Column::new("role".into(), DataType::VChar),
+            ]),
+            values: vec![Value::I32(1), Value::String("Alex"), Value::String("Admin")],
+        };
+        let de = || RowDeserializer::new(&row);
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Role {
+            Admin,
+            Guest,
+        }
+
+        assert_eq!(
+            <(i32, String, Role)>::deserialize(de()),
+            Ok((1, "Alex".into(), Role::Admin))
+        );
+        assert!(<(i32, String)>::deserialize(de()).is_err());
+        assert!(<(i32, String, Role, bool)>::deserialize(de()).is_err());
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Tuple(i32, String, String);
+        assert_eq!(
+            Tuple::deserialize(de()),
+            Ok(Tuple(1, "Alex".into(), "Admin".into()))
+        );
+
+        let map = HashMap::<String, serde_json::Value>::deserialize(de()).unwrap();
+        assert_eq!(map["user_name"], "Alex");
+        assert_eq!(map.len(), 3);
+
+        assert_eq!(
+            Vec::<String>::deserialize(de()).unwrap_err().to_string(),
+            "invalid type: integer `1`, expected a string"
+        );
+        assert_eq!(
+            Vec::<serde_json::Value>::deserialize(de()),
+            Ok(vec![1.into(), "Alex".into(), "Admin".into()])
+        );
+        assert!(i32::deserialize(de()).is_err());
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Camel {
+            user_name: String,
+        }
+        let camel = Row {
+            columns: Columns::new(vec![Column::new("userName".into(), DataType::VChar)]),
+            values: vec![Value::String("Alex")],
+        };
+        assert_eq!(
+            Camel::deserialize(RowDeserializer::new(&camel)),
+            Ok(Camel {
+                user_name: "Alex".into()
+            })
+        );
+        assert_eq!(
+            String::deserialize(RowDeserializer::new(&camel)),
+            Ok("Alex".into())
+        );
+        assert_eq!(
+            Option::<String>::deserialize(RowDeserializer::new(&camel)),
+            Ok(Some("Alex".into()))
+        );
+        assert_eq!(
+            Option::<Camel>::deserialize(RowDeserializer::new(&camel)),
+            Ok(Some(Camel {
+                user_name: "Alex".into()
+            }))
+        );
+        let null = Row {
+            columns: camel.columns.clone(),
+            values: vec![Value::Null],
+        };
+        assert_eq!(
+            Option::<String>::deserialize(RowDeserializer::new(&null)),
+            Ok(None)
+        );
+        assert_eq!(
+            Option::<Camel>::deserialize(RowDeserializer::new(&null)),
+            Ok(None)
+        );
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Profile {
+            user_name: String,
+            role: Role,
+        }
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct User {
+            #[serde(rename = "ID")]
+            id: i32,
+            #[serde(flatten)]
+            profile: Profile,
+        }
+        assert_eq!(
+            User::deserialize(de()),
+            Ok(User {
+                id: 1,
+                profile: Profile {
+                    user_name: "Alex".into(),
+                    role: Role::Admin,
+                },
+            })
+        );
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Account {
+            id: i32,
+            role: Role,
+        }
+        assert!(Account::deserialize(de()).is_err());
+        assert_eq!(
+            Account::deserialize(de().case_insensitive(true)),
+            Ok(Account {
+                id: 1,
+                role: Role::Admin,
+            })
+        );
+        assert_eq!(
+            row.deserialize_case_insensitive::<Option<Account>>()
+                .unwrap()
+                .unwrap()
+                .id,
+            1
+        );
     }
 }
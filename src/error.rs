@@ -1,3 +1,4 @@
+use crate::*;
 use std::ffi::NulError;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -14,4 +15,71 @@ pub enum Error {
     ClearBindings,
     #[error("Bind params error")]
     BindParams,
+    #[error("Missing value for parameter {0}")]
+    MissingParam(String),
+    #[error("Statement has no parameter named {0}")]
+    UnknownParam(String),
+    #[error("Parameter {0} is given more than once")]
+    DuplicateParam(String),
+    #[error("Invalid identifier '{0}'")]
+    InvalidIdentifier(String),
+    #[error("Open error: {0}")]
+    Open(String),
+    #[error("Invalid DSN: {0}")]
+    InvalidDsn(String),
+    #[error("Connection is read-only")]
+    ReadOnly,
+    #[error("Transaction was marked rollback-only by a nested scope")]
+    RollbackOnly,
+    #[error("Transaction failed after {0} attempts: {1}")]
+    RetriesExhausted(u32, Box<Error>),
+    #[error("Pool error: {0}")]
+    Pool(String),
+    #[error("Timed out waiting for a pooled connection")]
+    PoolTimeout,
+    #[error("Deserialize error: {0}")]
+    Deserialize(#[from] serde::de::value::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Decode error: {0}")]
+    Decode(String),
+    #[error("Connection worker has stopped")]
+    Disconnected,
+    #[error("Connection worker job panicked: {0}")]
+    Panic(String),
+    #[error("Conversion error: {0}")]
+    FromValue(String),
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
+    #[error("Column '{name}' ({datatype}): {source}")]
+    Column {
+        name: String,
+        datatype: DataType,
+        source: Box<Error>,
+    },
+    #[error("Query returned no rows")]
+    NoRows,
+    #[error("Query returned more than one row")]
+    TooManyRows,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Script error at line {line}: {source}")]
+    Script {
+        line: usize,
+        statement: String,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// The CrossDB error code reported by `xdb_errcode`, if any.
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Error::Query(code, _) => Some(*code),
+            Error::RetriesExhausted(_, err) => err.code(),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,230 @@
+use crate::*;
+use cidr::IpInet;
+use mac_address::MacAddress;
+
+/// Conversion from a column [`Value`], used by [`Row::get_as`].
+///
+/// Implemented for the integer widths (range-checked), floats, `bool`, strings,
+/// bytes, [`IpInet`], [`MacAddress`] and `Option<T>` which maps NULL to `None`.
+/// Implement it for newtypes by delegating to an existing impl:
+///
+/// ```
+/// use crossdb::{FromValue, Result, Value};
+///
+/// struct UserId(u32);
+///
+/// impl FromValue<'_> for UserId {
+///     fn from_value(value: &Value<'_>) -> Result<Self> {
+///         u32::from_value(value).map(UserId)
+///     }
+/// }
+/// ```
+pub trait FromValue<'a>: Sized {
+    fn from_value(value: &Value<'a>) -> Result<Self>;
+}
+
+pub(crate) fn invalid_type<T>(value: &Value<'_>) -> Error {
+    match value {
+        Value::Null => Error::FromValue(format!(
+            "unexpected NULL for {}, use Option",
+            std::any::type_name::<T>()
+        )),
+        value => Error::FromValue(format!(
+            "cannot convert {value:?} to {}",
+            std::any::type_name::<T>()
+        )),
+    }
+}
+
+pub(crate) fn out_of_range<T>(value: impl Display) -> Error {
+    Error::FromValue(format!(
+        "value {value} out of range for {}",
+        std::any::type_name::<T>()
+    ))
+}
+
+macro_rules! impl_from_value_int {
+    ($($t: ty),*) => {
+        $(
+            impl FromValue<'_> for $t {
+                fn from_value(value: &Value<'_>) -> Result<Self> {
+                    match *value {
+                        Value::I8(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::I16(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::I32(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::I64(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::U8(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::U16(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::U32(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::U64(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        Value::Timestamp(v) => v.try_into().map_err(|_| out_of_range::<$t>(v)),
+                        _ => Err(invalid_type::<$t>(value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_from_value_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl FromValue<'_> for f32 {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        match *value {
+            Value::F32(v) => Ok(v),
+            Value::I8(v) => Ok(v.into()),
+            Value::I16(v) => Ok(v.into()),
+            Value::U8(v) => Ok(v.into()),
+            Value::U16(v) => Ok(v.into()),
+            _ => Err(invalid_type::<f32>(value)),
+        }
+    }
+}
+
+impl FromValue<'_> for f64 {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        match *value {
+            Value::F32(v) => Ok(v.into()),
+            Value::F64(v) => Ok(v),
+            Value::I8(v) => Ok(v.into()),
+            Value::I16(v) => Ok(v.into()),
+            Value::I32(v) => Ok(v.into()),
+            Value::U8(v) => Ok(v.into()),
+            Value::U16(v) => Ok(v.into()),
+            Value::U32(v) => Ok(v.into()),
+            _ => Err(invalid_type::<f64>(value)),
+        }
+    }
+}
+
+impl FromValue<'_> for bool {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        match *value {
+            Value::Bool(v) => Ok(v),
+            _ => Err(invalid_type::<bool>(value)),
+        }
+    }
+}
+
+impl<'a> FromValue<'a> for &'a str {
+    fn from_value(value: &Value<'a>) -> Result<Self> {
+        match *value {
+            Value::String(v) | Value::Json(v) => Ok(v),
+            _ => Err(invalid_type::<&str>(value)),
+        }
+    }
+}
+
+impl FromValue<'_> for String {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        <&str>::from_value(value).map(String::from)
+    }
+}
+
+impl<'a> FromValue<'a> for &'a [u8] {
+    fn from_value(value: &Value<'a>) -> Result<Self> {
+        match *value {
+            Value::Binary(v) => Ok(v),
+            Value::String(v) => Ok(v.as_bytes()),
+            _ => Err(invalid_type::<&[u8]>(value)),
+        }
+    }
+}
+
+impl FromValue<'_> for Vec<u8> {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        <&[u8]>::from_value(value).map(Vec::from)
+    }
+}
+
+impl FromValue<'_> for IpInet {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        match *value {
+            Value::Inet(v) => Ok(v),
+            _ => Err(invalid_type::<IpInet>(value)),
+        }
+    }
+}
+
+impl FromValue<'_> for MacAddress {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        match *value {
+            Value::Mac(v) => Ok(v),
+            _ => Err(invalid_type::<MacAddress>(value)),
+        }
+    }
+}
+
+impl<'a, T: FromValue<'a>> FromValue<'a> for Option<T> {
+    fn from_value(value: &Value<'a>) -> Result<Self> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl<'a> FromValue<'a> for Value<'a> {
+    fn from_value(value: &Value<'a>) -> Result<Self> {
+        Ok(*value)
+    }
+}
+
+impl FromValue<'_> for OwnedValue {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        Ok(value.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_value() {
+        assert_eq!(i8::from_value(&Value::I32(18)).unwrap(), 18);
+        assert_eq!(u64::from_value(&Value::I64(7)).unwrap(), 7);
+        assert_eq!(
+            i64::from_value(&Value::U32(u32::MAX)).unwrap(),
+            u32::MAX as i64
+        );
+        assert_eq!(f64::from_value(&Value::F32(1.5)).unwrap(), 1.5);
+        assert!(bool::from_value(&Value::Bool(true)).unwrap());
+        assert_eq!(<&str>::from_value(&Value::String("Alex")).unwrap(), "Alex");
+        assert_eq!(String::from_value(&Value::String("Alex")).unwrap(), "Alex");
+        assert_eq!(
+            Vec::<u8>::from_value(&Value::Binary(&[1, 2])).unwrap(),
+            vec![1, 2]
+        );
+        let mac = MacAddress::new([1, 2, 3, 4, 5, 6]);
+        assert_eq!(MacAddress::from_value(&Value::Mac(mac)).unwrap(), mac);
+        assert_eq!(Option::<i32>::from_value(&Value::Null).unwrap(), None);
+        assert_eq!(Option::<i32>::from_value(&Value::I32(1)).unwrap(), Some(1));
+
+        assert_eq!(i8::from_value(&Value::I8(-128)).unwrap(), -128);
+        assert_eq!(i32::from_value(&Value::I16(-300)).unwrap(), -300);
+        assert_eq!(u16::from_value(&Value::U16(u16::MAX)).unwrap(), u16::MAX);
+        assert_eq!(i16::from_value(&Value::U8(255)).unwrap(), 255);
+        assert_eq!(f64::from_value(&Value::I8(-1)).unwrap(), -1.0);
+        assert_eq!(f32::from_value(&Value::I16(-300)).unwrap(), -300.0);
+        assert_eq!(f32::from_value(&Value::U16(u16::MAX)).unwrap(), 65535.0);
+    }
+
+    #[test]
+    fn test_from_value_error() {
+        let err = i8::from_value(&Value::I32(300)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conversion error: value 300 out of range for i8"
+        );
+        assert!(u32::from_value(&Value::I32(-1)).is_err());
+        assert!(i8::from_value(&Value::I16(128)).is_err());
+        assert!(u8::from_value(&Value::I8(-1)).is_err());
+        assert!(i8::from_value(&Value::U8(128)).is_err());
+        assert!(i16::from_value(&Value::U16(40000)).is_err());
+        assert!(i32::from_value(&Value::Null).is_err());
+        assert!(i32::from_value(&Value::String("1")).is_err());
+        assert!(String::from_value(&Value::I32(1)).is_err());
+        // Wider integers do not fit an f32 exactly.
+        assert!(f32::from_value(&Value::I32(1)).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// Binds any [`Serialize`] type as a JSON parameter, and reads a JSON column
+/// into any [`Deserialize`] type.
+///
+/// ```no_run
+/// use crossdb::{Connection, Json};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Meta {
+///     tags: Vec<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Doc {
+///     id: i32,
+///     meta: Meta,
+/// }
+///
+/// let mut conn = Connection::open_with_memory()?;
+/// conn.execute("CREATE TABLE docs(id INT, meta JSON);")?;
+/// conn.prepare("INSERT INTO docs (id, meta) values (?, ?);")?
+///     .execute((1, Json(Meta { tags: vec!["a".into()] })))?;
+///
+/// let doc: Doc = conn.query_one("SELECT id, meta FROM docs;")?;
+/// assert_eq!(doc.meta.tags, ["a"]);
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> Serialize for Json<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Json<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Json)
+    }
+}
+
+impl<T: Serialize> ToParam for Json<T> {
+    fn to_param(&self) -> Result<ParamValue> {
+        let json = serde_json::to_string(&self.0)?;
+        Ok(ParamValue::Json(CString::new(json)?))
+    }
+}
+
+impl ToParam for serde_json::Value {
+    fn to_param(&self) -> Result<ParamValue> {
+        Json(self).to_param()
+    }
+}
+
+// String columns are accepted too, for JSON kept in VARCHAR.
+impl<'a, T: Deserialize<'a>> FromValue<'a> for Json<T> {
+    fn from_value(value: &Value<'a>) -> Result<Self> {
+        let json = <&'a str>::from_value(value)?;
+        Ok(Json(serde_json::from_str(json)?))
+    }
+}
+
+impl FromValue<'_> for serde_json::Value {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        Json::from_value(value).map(|v| v.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Meta<'a> {
+        source: &'a str,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_json() {
+        let doc = r#"{"source":"import","tags":["a","b"]}"#;
+        let meta = Meta {
+            source: "import",
+            tags: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(
+            Json(&meta).to_param().unwrap(),
+            ParamValue::Json(CString::new(doc).unwrap())
+        );
+        assert_eq!(
+            json!({"source": "import", "tags": ["a", "b"]})
+                .to_param()
+                .unwrap(),
+            ParamValue::Json(CString::new(doc).unwrap())
+        );
+
+        let Json(v) = Json::<Meta>::from_value(&Value::Json(doc)).unwrap();
+        assert_eq!(v, meta);
+        let v = serde_json::Value::from_value(&Value::String(doc)).unwrap();
+        assert_eq!(v["tags"][1], "b");
+
+        assert!(matches!(
+            Json::<Meta>::from_value(&Value::Json("{")),
+            Err(Error::Json(_))
+        ));
+        assert!(Json::<Meta>::from_value(&Value::I32(1)).is_err());
+        assert_eq!(
+            Option::<Json<Meta>>::from_value(&Value::Null).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_json_column() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Doc {
+            id: i32,
+            meta: Json<serde_json::Value>,
+        }
+
+        let mut conn = OpenOptions::new().memory("json_column").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS docs(id INT, meta JSON);")
+            .unwrap();
+        let stmt = conn
+            .prepare("INSERT INTO docs (id, meta) values (?, ?);")
+            .unwrap();
+        let meta = Meta {
+            source: "import",
+            tags: vec!["a".into()],
+        };
+        stmt.execute((1, Json(&meta))).unwrap();
+
+        let doc: Doc = conn.query_one("SELECT id, meta FROM docs;").unwrap();
+        assert_eq!(doc.meta.0, json!({"source": "import", "tags": ["a"]}));
+
+        let mut query = conn.query("SELECT meta FROM docs;").unwrap();
+        let row = query.fetch_row().unwrap().unwrap();
+        assert_eq!(row.get_as::<Json<Meta>>(0).unwrap().0, meta);
+    }
+}
@@ -10,44 +10,90 @@ mod crossdb_sys {
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 }
 
+#[cfg(feature = "tokio")]
+mod async_connection;
+mod batch;
 mod column;
 mod de;
 mod error;
+mod from_value;
+mod json;
+mod options;
 mod params;
+mod pool;
+mod retry;
 mod row;
+mod rows;
+mod ser;
 mod statement;
+pub mod timestamp;
+mod transaction;
 mod value;
 
+#[cfg(feature = "tokio")]
+pub use async_connection::{AsyncConnection, AsyncStatement, RowStream};
+pub use batch::Batch;
 pub use column::{Column, Columns, ColumnsIter, DataType};
 pub use error::{Error, Result};
-pub use params::{IntoParams, Params, Value as ParamValue};
-pub use row::{IntoValueIndex, Row, ValueIndex};
+pub use from_value::FromValue;
+pub use json::Json;
+pub use options::{OpenMode, OpenOptions};
+pub use params::{IntoParams, Params, ToParam, Value as ParamValue};
+pub use pool::{Pool, PoolBuilder, PoolState, PooledConnection};
+pub use retry::{Backoff, NoRetry, RetryPolicy};
+pub use row::{IntoValueIndex, OwnedRow, Row, ValueIndex};
+pub use rows::{IntoIterAs, MappedRows, Rows, SerializeRows};
+pub use ser::to_params;
 pub use statement::Statement;
-pub use value::Value;
+pub use transaction::Transaction;
+pub use value::{OwnedValue, Value};
 
 use crossdb_sys::*;
 use lru::LruCache;
 use serde::de::{value::Error as DeError, DeserializeOwned};
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::ffi::{CStr, CString};
 use std::fmt::Display;
+use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::slice::from_raw_parts;
 
-pub fn version() -> &'static str {
-    unsafe { CStr::from_ptr(xdb_version()).to_str().unwrap() }
+/// The CrossDB engine version, with any invalid UTF-8 replaced.
+pub fn version() -> Cow<'static, str> {
+    unsafe { CStr::from_ptr(xdb_version()).to_string_lossy() }
 }
 
+/// A connection to a CrossDB database.
+///
+/// # Thread safety
+///
+/// A `Connection` is [`Send`] but not [`Sync`]: it can be moved to another thread,
+/// but the engine handle and its statement cache must not be used from two threads
+/// at once. To work from several threads, open one connection per thread on the
+/// same database, or use a pool. [`Statement`] follows the same rules, while a
+/// [`Query`] borrows its connection and stays on its thread.
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<crossdb::Connection>();
+/// ```
 #[derive(Debug)]
 pub struct Connection {
     ptr: *mut xdb_conn_t,
     cache: LruCache<CString, Statement>,
+    read_only: bool,
+    tx_depth: Cell<usize>,
+    tx_rollback_only: Cell<bool>,
 }
 
+// SAFETY: the connection exclusively owns its engine handle and cached statements.
 unsafe impl Send for Connection {}
-unsafe impl Sync for Connection {}
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        // Cached statements must be closed before the connection they belong to.
+        self.cache.clear();
         unsafe {
             xdb_close(self.ptr);
         }
@@ -56,28 +102,57 @@ impl Drop for Connection {
 
 impl Connection {
     pub fn open<P: AsRef<str>>(path: P) -> Result<Self> {
-        let path = CString::new(path.as_ref())?;
-        let ptr = unsafe { xdb_open(path.as_ptr()) };
-        let cap = NonZeroUsize::new(256).unwrap();
+        let cap = NonZeroUsize::new(options::DEFAULT_STATEMENT_CACHE_CAPACITY).unwrap();
+        Self::open_path(path.as_ref(), cap)
+    }
+
+    pub(crate) fn open_path(path: &str, cache_capacity: NonZeroUsize) -> Result<Self> {
+        let c_path = CString::new(path)?;
+        let ptr = unsafe { xdb_open(c_path.as_ptr()) };
+        let conn = Self::from_ptr(ptr, cache_capacity)?;
+        if unsafe { xdb_curdb(conn.ptr) }.is_null() {
+            return Err(Error::Open(format!("failed to open database '{path}'")));
+        }
+        Ok(conn)
+    }
+
+    pub fn open_with_memory() -> Result<Self> {
+        Self::open(":memory:")
+    }
+
+    /// Opens a connection configured by a DSN, see [`OpenOptions::from_dsn`].
+    pub fn open_dsn<S: AsRef<str>>(dsn: S) -> Result<Self> {
+        OpenOptions::from_dsn(dsn.as_ref())?.open()
+    }
+
+    pub(crate) fn from_ptr(ptr: *mut xdb_conn_t, cache_capacity: NonZeroUsize) -> Result<Self> {
+        if ptr.is_null() {
+            return Err(Error::Open("failed to allocate connection".into()));
+        }
         Ok(Self {
             ptr,
-            cache: LruCache::new(cap),
+            cache: LruCache::new(cache_capacity),
+            read_only: false,
+            tx_depth: Cell::new(0),
+            tx_rollback_only: Cell::new(false),
         })
     }
 
-    pub fn open_with_memory() -> Result<Self> {
-        Self::open(":memory:")
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
     }
 
-    pub fn current_database(&self) -> Result<&str> {
+    // The engine reuses this buffer on `USE`, so the name is copied out.
+    pub fn current_database(&self) -> Result<String> {
         unsafe {
             let ptr = xdb_curdb(self.ptr);
             let db = CStr::from_ptr(ptr).to_str()?;
-            Ok(db)
+            Ok(db.to_string())
         }
     }
 
-    pub fn query<S: AsRef<str>>(&self, sql: S) -> Result<Query> {
+    pub fn query<S: AsRef<str>>(&self, sql: S) -> Result<Query<'_>> {
+        self.check_read_only(sql.as_ref())?;
         let sql = CString::new(sql.as_ref())?;
         unsafe {
             let ptr = xdb_exec(self.ptr, sql.as_ptr());
@@ -89,26 +164,130 @@ impl Connection {
         self.query(sql).map(|q| q.affected_rows())
     }
 
-    pub fn begin(&self) -> bool {
-        unsafe { xdb_begin(self.ptr) == 0 }
+    /// Begins a transaction, or a nested scope inside an open one.
+    ///
+    /// Shares its bookkeeping with [`Transaction`], see there for how nested
+    /// scopes commit and roll back.
+    pub fn begin(&self) -> Result<()> {
+        if self.tx_depth.get() == 0 {
+            tx_result(unsafe { xdb_begin(self.ptr) }, "BEGIN")?;
+            self.tx_rollback_only.set(false);
+        }
+        self.tx_depth.set(self.tx_depth.get() + 1);
+        Ok(())
     }
 
-    pub fn commit(&self) -> bool {
-        unsafe { xdb_commit(self.ptr) == 0 }
+    pub fn commit(&self) -> Result<()> {
+        self.end_transaction(true)
     }
 
-    pub fn rollback(&self) -> bool {
-        unsafe { xdb_rollback(self.ptr) == 0 }
+    pub fn rollback(&self) -> Result<()> {
+        self.end_transaction(false)
     }
 
-    pub fn prepare<S: AsRef<str>>(&mut self, sql: S) -> Result<&Statement> {
-        let sql = CString::new(sql.as_ref())?;
-        let sql_ptr = sql.as_ptr();
-        let stmt = self.cache.get_or_insert(sql, || {
-            let ptr = unsafe { xdb_stmt_prepare(self.ptr, sql_ptr) };
-            Statement { ptr }
-        });
-        Ok(stmt)
+    fn end_transaction(&self, commit: bool) -> Result<()> {
+        match self.tx_depth.get() {
+            // Not tracked as open, let the engine decide.
+            0 if commit => return self.raw_commit(),
+            0 => return self.raw_rollback(),
+            1 => self.tx_depth.set(0),
+            depth => {
+                self.tx_depth.set(depth - 1);
+                if !commit {
+                    self.tx_rollback_only.set(true);
+                }
+                return Ok(());
+            }
+        }
+
+        if commit && !self.tx_rollback_only.take() {
+            return self.raw_commit().inspect_err(|_| {
+                let _ = self.raw_rollback();
+            });
+        }
+        self.tx_rollback_only.set(false);
+        self.raw_rollback()?;
+        match commit {
+            true => Err(Error::RollbackOnly),
+            false => Ok(()),
+        }
+    }
+
+    // Rolls back whatever transaction is open, at any depth.
+    pub(crate) fn reset_transaction(&self) -> Result<()> {
+        self.tx_depth.set(0);
+        self.tx_rollback_only.set(false);
+        self.raw_rollback()
+    }
+
+    fn raw_commit(&self) -> Result<()> {
+        tx_result(unsafe { xdb_commit(self.ptr) }, "COMMIT")
+    }
+
+    fn raw_rollback(&self) -> Result<()> {
+        tx_result(unsafe { xdb_rollback(self.ptr) }, "ROLLBACK")
+    }
+
+    /// Nesting depth of the open transaction, `0` outside of one.
+    pub fn transaction_depth(&self) -> usize {
+        self.tx_depth.get()
+    }
+
+    pub fn transaction(&mut self) -> Result<Transaction<'_>> {
+        Transaction::new(self)
+    }
+
+    /// Runs `f` in a transaction, committing on `Ok` and rolling back on `Err`.
+    ///
+    /// Failures are not retried: CrossDB waits on locks instead of failing and
+    /// reports no conflict error, so there is nothing worth retrying by default.
+    /// Use [`Connection::transaction_with_retry`] to retry errors of your choosing.
+    pub fn transaction_with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T>,
+    {
+        let mut tx = self.transaction()?;
+        let value = f(&mut tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Like [`Connection::transaction_with`], retrying the errors `policy` accepts.
+    ///
+    /// On success returns the value together with the number of attempts made.
+    /// Once the policy gives up after retrying, the last error is returned wrapped in
+    /// [`Error::RetriesExhausted`] together with the number of attempts made. A policy
+    /// that never retries, such as [`NoRetry`], returns the original error.
+    /// Nested transactions are never retried since only the outermost one can roll back.
+    pub fn transaction_with_retry<T, F, P>(&mut self, policy: &mut P, mut f: F) -> Result<(T, u32)>
+    where
+        F: FnMut(&mut Transaction<'_>) -> Result<T>,
+        P: RetryPolicy + ?Sized,
+    {
+        let nested = self.tx_depth.get() > 0;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let err = match self.transaction_with(&mut f) {
+                Ok(value) => return Ok((value, attempt)),
+                Err(err) if !nested && policy.is_retryable(&err) => err,
+                Err(err) => return Err(err),
+            };
+            match policy.next_delay(attempt, &err) {
+                Some(delay) => std::thread::sleep(delay),
+                None if attempt == 1 => return Err(err),
+                None => return Err(Error::RetriesExhausted(attempt, Box::new(err))),
+            }
+        }
+    }
+
+    pub fn prepare<S: AsRef<str>>(&mut self, sql: S) -> Result<&mut Statement> {
+        let sql = sql.as_ref();
+        self.check_read_only(sql)?;
+        let key = CString::new(sql)?;
+        let conn = self.ptr;
+        self.cache
+            .try_get_or_insert_mut(key, || Statement::prepare(conn, sql))
     }
 
     pub fn resize_statement_cache(&mut self, capacity: usize) {
@@ -119,15 +298,65 @@ impl Connection {
     pub fn clear_statement_cache(&mut self) {
         self.cache.clear();
     }
+
+    fn check_read_only(&self, sql: &str) -> Result<()> {
+        if !self.read_only {
+            return Ok(());
+        }
+        const ALLOWED: [&str; 10] = [
+            "SELECT", "SHOW", "DESCRIBE", "DESC", "EXPLAIN", "USE", "BEGIN", "START", "COMMIT",
+            "ROLLBACK",
+        ];
+        // Every statement of a multi-statement string has to be a read.
+        for (_, statement) in batch::split_statements(sql) {
+            let keyword = statement.split_whitespace().next().unwrap_or_default();
+            if !ALLOWED.iter().any(|k| k.eq_ignore_ascii_case(keyword)) {
+                return Err(Error::ReadOnly);
+            }
+        }
+        Ok(())
+    }
+}
+
+// `xdb_begin`, `xdb_commit` and `xdb_rollback` return `0` on success.
+fn tx_result(ret: xdb_ret, op: &str) -> Result<()> {
+    match ret {
+        0 => Ok(()),
+        code => Err(Error::Query(code.unsigned_abs(), format!("{op} failed"))),
+    }
 }
 
+/// The result of a query, borrowing the [`Connection`] (or [`Statement`]) it came from
+/// so the result buffer cannot outlive the engine that owns it.
+///
+/// ```compile_fail
+/// let conn = crossdb::Connection::open_with_memory().unwrap();
+/// let query = conn.query("SELECT * FROM system.databases;").unwrap();
+/// drop(conn);
+/// query.row_count();
+/// ```
+///
+/// It is not [`Send`], since fetching rows on another thread would race with the
+/// connection that owns the result. Use [`Query::collect_owned`] to hand rows over.
+///
+/// ```compile_fail
+/// let conn = crossdb::Connection::open_with_memory().unwrap();
+/// let mut query = conn.query("SELECT * FROM system.databases;").unwrap();
+/// std::thread::scope(|s| {
+///     s.spawn(move || query.fetch_row().map(|_| ()));
+///     conn.execute("SELECT 1;").unwrap();
+/// });
+/// ```
 #[derive(Debug)]
-pub struct Query {
+pub struct Query<'conn> {
     ptr: *mut xdb_res_t,
     columns: Columns,
+    case_insensitive: bool,
+    fetched: usize,
+    _conn: PhantomData<&'conn Connection>,
 }
 
-impl Drop for Query {
+impl Drop for Query<'_> {
     fn drop(&mut self) {
         unsafe {
             xdb_free_result(self.ptr);
@@ -135,19 +364,28 @@ impl Drop for Query {
     }
 }
 
-unsafe impl Send for Query {}
-unsafe impl Sync for Query {}
-
-impl Query {
+impl Query<'_> {
     pub(crate) unsafe fn from_res(ptr: *mut xdb_res_t) -> Result<Self> {
         let code = xdb_errcode(ptr);
         if code != xdb_errno_e_XDB_OK {
-            let msg = CStr::from_ptr(xdb_errmsg(ptr)).to_str()?.to_string();
+            let msg = CStr::from_ptr(xdb_errmsg(ptr))
+                .to_string_lossy()
+                .into_owned();
             return Err(Error::Query(code, msg));
         }
+        let columns = match Columns::from_res(ptr) {
+            Ok(columns) => columns,
+            Err(err) => {
+                xdb_free_result(ptr);
+                return Err(err);
+            }
+        };
         Ok(Self {
             ptr,
-            columns: Columns::from_res(ptr),
+            columns,
+            case_insensitive: false,
+            fetched: 0,
+            _conn: PhantomData,
         })
     }
 
@@ -167,37 +405,70 @@ impl Query {
         &self.columns
     }
 
-    pub fn fetch_row(&mut self) -> Option<Row<'_>> {
+    /// Fetches the next row, `None` once the result is exhausted.
+    ///
+    /// Fails if a value cannot be decoded, the error names the column.
+    pub fn fetch_row(&mut self) -> Result<Option<Row<'_>>> {
         let columns = self.columns.clone();
         let values = self.inner_fetch_row_values()?;
-        Some(Row { columns, values })
+        Ok(values.map(|values| Row { columns, values }))
     }
 
-    pub fn fetch_row_as<T: DeserializeOwned>(&mut self) -> Option<Result<T, DeError>> {
-        self.fetch_row().map(|row| row.deserialize())
+    /// Matches column names to struct fields ignoring ASCII case when rows are
+    /// deserialized, see [`Row::deserialize_case_insensitive`].
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
     }
 
-    pub fn fetch_rows_as<T: DeserializeOwned>(&mut self) -> Result<Vec<T>, DeError> {
+    pub fn fetch_row_as<T: DeserializeOwned>(&mut self) -> Option<Result<T>> {
+        let case_insensitive = self.case_insensitive;
+        match self.fetch_row() {
+            Ok(row) => row.map(|row| Ok(row.deserialize_with(case_insensitive)?)),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    pub fn fetch_rows_as<T: DeserializeOwned>(&mut self) -> Result<Vec<T>> {
+        let case_insensitive = self.case_insensitive;
         let mut rows = Vec::with_capacity(self.row_count());
-        while let Some(row) = self.fetch_row() {
-            rows.push(row.deserialize()?);
+        while let Some(row) = self.fetch_row()? {
+            rows.push(row.deserialize_with(case_insensitive)?);
         }
         Ok(rows)
     }
 
-    fn inner_fetch_row_values(&mut self) -> Option<Vec<Value<'_>>> {
+    /// Copies the remaining rows out of the result, so they can be kept after
+    /// the query is dropped or sent to other threads.
+    pub fn collect_owned(&mut self) -> Result<Vec<OwnedRow>> {
+        let mut rows = Vec::with_capacity(self.row_count());
+        while let Some(row) = self.fetch_row()? {
+            rows.push(row.to_owned());
+        }
+        Ok(rows)
+    }
+
+    fn inner_fetch_row_values(&mut self) -> Result<Option<Vec<Value<'_>>>> {
         unsafe {
             let row = xdb_fetch_row(self.ptr);
             if row.is_null() {
-                return None;
+                return Ok(None);
             }
+            self.fetched += 1;
             let count = self.columns.len();
             let mut values = Vec::with_capacity(count);
             for i in 0..count {
-                let v = Value::from_ptr(self.ptr, row, i as u16, self.columns.datatype(i));
+                let datatype = self.columns.datatype(i);
+                let v = Value::from_ptr(self.ptr, row, i as u16, datatype).map_err(|err| {
+                    Error::Column {
+                        name: self.columns.name(i).to_string(),
+                        datatype,
+                        source: Box::new(err),
+                    }
+                })?;
                 values.push(v);
             }
-            Some(values)
+            Ok(Some(values))
         }
     }
 }
@@ -232,24 +503,196 @@ mod tests {
         assert_eq!(query.columns.name(2), "age");
         assert_eq!(query.columns.datatype(2), DataType::TinyInt);
 
-        let row1 = query.fetch_row().unwrap();
+        let row1 = query.fetch_row().unwrap().unwrap();
         assert_eq!(row1.get(0), &Value::I32(1));
         assert_eq!(row1.get(1), &Value::String("Alex"));
-        assert_eq!(row1.get(2), &Value::I32(18));
+        assert_eq!(row1.get(2), &Value::I8(18));
 
-        let row2 = query.fetch_row().unwrap();
+        let row2 = query.fetch_row().unwrap().unwrap();
         assert_eq!(row2.get(0), &Value::I32(2));
         assert_eq!(row2.get(1), &Value::String("Thorne"));
-        assert_eq!(row2.get(2), &Value::I32(22));
+        assert_eq!(row2.get(2), &Value::I8(22));
 
-        let row3 = query.fetch_row().unwrap();
+        let row3 = query.fetch_row().unwrap().unwrap();
         assert_eq!(row3.get(0), &Value::I32(3));
         assert_eq!(row3.get(1), &Value::String("Ryder"));
-        assert_eq!(row3.get(2), &Value::I32(36));
+        assert_eq!(row3.get(2), &Value::I8(36));
 
-        assert!(query.fetch_row().is_none());
+        assert!(query.fetch_row().unwrap().is_none());
 
         let affected_rows = conn.execute("DELETE FROM users;").unwrap();
         assert_eq!(affected_rows, 3);
     }
+
+    #[test]
+    fn test_bind_types() {
+        let mut conn = OpenOptions::new().memory("bind_types").open().unwrap();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS t(b BOOL, u UINT, ub UBIGINT, d DOUBLE, ts TIMESTAMP, \
+             s VARCHAR(16), bin VARBINARY(16), ip INET, mac MAC, n INT);",
+        )
+        .unwrap();
+        let inet: cidr::IpInet = "192.168.1.1/24".parse().unwrap();
+        let mac = mac_address::MacAddress::new([1, 2, 3, 4, 5, 6]);
+        let sql = "INSERT INTO t (b, u, ub, d, ts, s, bin, ip, mac, n) \
+                   values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);";
+        let bin: &[u8] = &[0, 1, 2];
+        conn.prepare(sql)
+            .unwrap()
+            .execute(params![
+                true,
+                u32::MAX,
+                u64::MAX,
+                1.5,
+                ParamValue::Timestamp(1_700_000_000_000_000),
+                "Alex",
+                bin,
+                inet,
+                mac,
+                None::<i32>
+            ])
+            .unwrap();
+
+        let mut query = conn.query("SELECT * FROM t;").unwrap();
+        let row = query.fetch_row().unwrap().unwrap();
+        let expected = [
+            Value::Bool(true),
+            Value::U32(u32::MAX),
+            Value::U64(u64::MAX),
+            Value::F64(1.5),
+            Value::Timestamp(1_700_000_000_000_000),
+            Value::String("Alex"),
+            Value::Binary(&[0, 1, 2]),
+            Value::Inet(inet),
+            Value::Mac(mac),
+            Value::Null,
+        ];
+        assert_eq!(row.values(), &expected);
+        let values: Vec<ParamValue> = row.values().iter().map(|v| v.to_param().unwrap()).collect();
+        drop(query);
+
+        // Values read back bind into an identical row.
+        conn.prepare(sql).unwrap().execute(values).unwrap();
+        let mut query = conn.query("SELECT * FROM t;").unwrap();
+        query.fetch_row().unwrap().unwrap();
+        assert_eq!(query.fetch_row().unwrap().unwrap().values(), &expected);
+    }
+
+    #[test]
+    fn test_empty_binary() {
+        let mut conn = OpenOptions::new().memory("empty_binary").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT, bin VARBINARY(16));")
+            .unwrap();
+        let stmt = conn
+            .prepare("INSERT INTO t (id, bin) values (?, ?);")
+            .unwrap();
+        stmt.execute((1, Vec::<u8>::new())).unwrap();
+        stmt.execute((2, None::<Vec<u8>>)).unwrap();
+
+        let mut query = conn.query("SELECT bin FROM t;").unwrap();
+        let row = query.fetch_row().unwrap().unwrap();
+        assert_eq!(row.get(0), &Value::Binary(&[]));
+        let row = query.fetch_row().unwrap().unwrap();
+        assert_eq!(row.get(0), &Value::Null);
+    }
+
+    #[test]
+    fn test_collect_owned() {
+        let mut conn = OpenOptions::new().memory("collect_owned").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT, name VARCHAR(16));")
+            .unwrap();
+        conn.execute("DELETE FROM t;").unwrap();
+        conn.execute("INSERT INTO t (id, name) values (1, 'Alex');")
+            .unwrap();
+
+        let rows = conn
+            .query("SELECT * FROM t;")
+            .unwrap()
+            .collect_owned()
+            .unwrap();
+        let rows = std::thread::spawn(move || rows).join().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get("name"), &OwnedValue::String("Alex".into()));
+
+        let stmt = conn.prepare("SELECT id FROM t WHERE name = ?;").unwrap();
+        let id: i32 = stmt.query_scalar((rows[0].get(1),)).unwrap();
+        assert_eq!(id, 1);
+    }
+
+    #[test]
+    fn test_multiple_results() {
+        let conn = OpenOptions::new()
+            .memory("multiple_results")
+            .open()
+            .unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+        conn.execute("INSERT INTO t (id) values (1);").unwrap();
+
+        let mut q1 = conn.query("SELECT * FROM t;").unwrap();
+        let mut q2 = conn.query("SELECT * FROM t;").unwrap();
+        assert_eq!(q1.fetch_row().unwrap().unwrap().get(0), &Value::I32(1));
+        assert_eq!(q2.fetch_row().unwrap().unwrap().get(0), &Value::I32(1));
+    }
+
+    #[test]
+    fn test_current_database() {
+        let conn = Connection::open_with_memory().unwrap();
+        let db = conn.current_database().unwrap();
+        assert_eq!(db, "memory");
+
+        conn.execute("CREATE DATABASE IF NOT EXISTS other ENGINE=MEMORY;")
+            .unwrap();
+        conn.execute("USE other;").unwrap();
+        assert_eq!(db, "memory");
+        assert_eq!(conn.current_database().unwrap(), "other");
+    }
+
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Connection>();
+        assert_send::<Statement>();
+        assert_send::<Columns>();
+        assert_send::<Row>();
+    }
+
+    #[test]
+    fn test_multi_thread() {
+        const THREADS: i32 = 8;
+        const ROWS: i32 = 200;
+
+        let conn = OpenOptions::new().memory("stress").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT, thread INT);")
+            .unwrap();
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                std::thread::spawn(move || {
+                    let mut conn = OpenOptions::new().memory("stress").open().unwrap();
+                    let stmt = conn
+                        .prepare("INSERT INTO t (id, thread) values (?, ?);")
+                        .unwrap();
+                    for id in 0..ROWS {
+                        stmt.execute((id, thread)).unwrap();
+                    }
+                    {
+                        let mut query = conn.query("SELECT * FROM t;").unwrap();
+                        let columns = query.columns().clone();
+                        while let Some(row) = query.fetch_row().unwrap() {
+                            assert_eq!(row.columns().len(), columns.len());
+                        }
+                    }
+                    conn
+                })
+            })
+            .collect();
+        for handle in handles {
+            // Connections are moved back to the spawning thread and dropped here.
+            handle.join().unwrap();
+        }
+
+        let query = conn.query("SELECT * FROM t;").unwrap();
+        assert_eq!(query.row_count(), (THREADS * ROWS) as usize);
+    }
 }
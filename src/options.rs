@@ -0,0 +1,337 @@
+use crate::*;
+use std::path::Path;
+use std::str::FromStr;
+
+pub(crate) const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 256;
+
+const DSN_SCHEME: &str = "crossdb://";
+const MEMORY_PATH: &str = ":memory:";
+const MEMORY_DATABASE: &str = "memory";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenMode {
+    #[default]
+    Memory,
+    Disk,
+}
+
+/// Options and flags which can be used to configure how a [`Connection`] is opened.
+///
+/// ```no_run
+/// use crossdb::OpenOptions;
+///
+/// let conn = OpenOptions::new()
+///     .path("data/app")
+///     .statement_cache_capacity(512)
+///     .open()?;
+///
+/// // Equivalent DSN form
+/// let conn = OpenOptions::from_dsn("crossdb://data/app?cache=512")?.open()?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenOptions {
+    mode: OpenMode,
+    path: String,
+    create: bool,
+    database: Option<String>,
+    cache_capacity: usize,
+    read_only: bool,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self {
+            mode: OpenMode::Memory,
+            path: MEMORY_DATABASE.to_string(),
+            create: true,
+            database: None,
+            cache_capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            read_only: false,
+        }
+    }
+}
+
+impl OpenOptions {
+    /// Creates options for an in-memory database named `memory`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a DSN such as `crossdb://data/app?cache=512&mode=disk`.
+    ///
+    /// Supported query parameters: `mode` (`memory` or `disk`), `create`, `db`,
+    /// `cache` and `readonly`.
+    pub fn from_dsn(dsn: &str) -> Result<Self> {
+        dsn.parse()
+    }
+
+    /// Opens an in-memory database with the given name.
+    pub fn memory<S: Into<String>>(mut self, name: S) -> Self {
+        self.mode = OpenMode::Memory;
+        self.path = name.into();
+        self
+    }
+
+    /// Opens an on-disk database at the given path.
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.mode = OpenMode::Disk;
+        self.path = path.into();
+        self
+    }
+
+    /// Whether to create the database if it does not exist, defaults to `true`.
+    pub fn create_if_missing(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    /// The database to `USE` once the connection is open.
+    pub fn database<S: Into<String>>(mut self, name: S) -> Self {
+        self.database = Some(name.into());
+        self
+    }
+
+    /// Capacity of the per-connection prepared statement cache, defaults to 256.
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Rejects every statement that is not a read (`SELECT`, `SHOW`, ...).
+    ///
+    /// This is enforced by the driver, the engine itself has no read-only mode.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn open(&self) -> Result<Connection> {
+        let cap = NonZeroUsize::new(self.cache_capacity)
+            .ok_or_else(|| Error::Open("statement cache capacity must be non-zero".into()))?;
+        if self.path.is_empty() {
+            return Err(Error::Open("empty database path".into()));
+        }
+        if let Some(database) = &self.database {
+            check_identifier(database)?;
+        }
+        let path = match self.mode {
+            // Named memory databases are created and selected with SQL from the
+            // default one, so the name must be a plain identifier.
+            OpenMode::Memory => {
+                check_identifier(&self.path)?;
+                MEMORY_PATH
+            }
+            OpenMode::Disk if !self.create && !Path::new(&self.path).exists() => {
+                return Err(Error::Open(format!("database '{}' not found", self.path)));
+            }
+            OpenMode::Disk => self.path.as_str(),
+        };
+
+        // `xdb_open` creates an on-disk database that does not exist yet.
+        let mut conn = Connection::open_path(path, cap)?;
+
+        if self.mode == OpenMode::Memory && self.path != MEMORY_DATABASE {
+            if self.create {
+                conn.execute(format!(
+                    "CREATE DATABASE IF NOT EXISTS {} ENGINE=MEMORY",
+                    self.path
+                ))?;
+            }
+            conn.execute(format!("USE {}", self.path))?;
+        }
+        if let Some(database) = &self.database {
+            conn.execute(format!("USE {database}"))?;
+        }
+
+        conn.read_only = self.read_only;
+        Ok(conn)
+    }
+}
+
+impl FromStr for OpenOptions {
+    type Err = Error;
+
+    fn from_str(dsn: &str) -> Result<Self> {
+        let rest = dsn
+            .strip_prefix(DSN_SCHEME)
+            .ok_or_else(|| Error::InvalidDsn(format!("expected '{DSN_SCHEME}' prefix")))?;
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (rest, ""),
+        };
+        let path = percent_decode(path)?;
+        let path = path.as_str();
+
+        let mut opts = match path {
+            "" | MEMORY_PATH => Self::new(),
+            path => Self::new().path(path),
+        };
+        for pair in query.split('&').filter(|s| !s.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidDsn(format!("missing value for '{pair}'")))?;
+            let (key, value) = (percent_decode(key)?, percent_decode(value)?);
+            let (key, value) = (key.as_str(), value.as_str());
+            match key {
+                "mode" => match value {
+                    "memory" => {
+                        opts.mode = OpenMode::Memory;
+                        if path.is_empty() || path == MEMORY_PATH {
+                            opts.path = MEMORY_DATABASE.to_string();
+                        }
+                    }
+                    "disk" => opts.mode = OpenMode::Disk,
+                    _ => return Err(Error::InvalidDsn(format!("unknown mode '{value}'"))),
+                },
+                "create" => opts.create = parse_bool(key, value)?,
+                "db" => opts.database = Some(value.to_string()),
+                "cache" => {
+                    opts.cache_capacity = value
+                        .parse()
+                        .ok()
+                        .filter(|cap| *cap != 0)
+                        .ok_or_else(|| Error::InvalidDsn(format!("invalid cache '{value}'")))?
+                }
+                "readonly" => opts.read_only = parse_bool(key, value)?,
+                _ => return Err(Error::InvalidDsn(format!("unknown parameter '{key}'"))),
+            }
+        }
+        if opts.mode == OpenMode::Disk && (path.is_empty() || path == MEMORY_PATH) {
+            return Err(Error::InvalidDsn("missing database path".into()));
+        }
+        Ok(opts)
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" | "1" | "on" => Ok(true),
+        "false" | "0" | "off" => Ok(false),
+        _ => Err(Error::InvalidDsn(format!("invalid {key} '{value}'"))),
+    }
+}
+
+// Decodes `%XX` escapes in a DSN component.
+fn percent_decode(s: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        if b != b'%' {
+            bytes.push(b);
+            continue;
+        }
+        let hex = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| Error::InvalidDsn(format!("invalid escape in '{s}'")))?;
+        bytes.push(hex);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).map_err(|_| Error::InvalidDsn(format!("invalid UTF-8 in '{s}'")))
+}
+
+// Database names are spliced into `CREATE DATABASE` and `USE`, so only plain
+// identifiers are accepted.
+fn check_identifier(name: &str) -> Result<()> {
+    match is_identifier(name) {
+        true => Ok(()),
+        false => Err(Error::Open(format!("invalid database name '{name}'"))),
+    }
+}
+
+/// Whether `name` is a plain `[A-Za-z_][A-Za-z0-9_]*` identifier.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dsn() {
+        let opts = OpenOptions::from_dsn("crossdb://:memory:").unwrap();
+        assert_eq!(opts, OpenOptions::new());
+
+        let opts = OpenOptions::from_dsn("crossdb://data/app?cache=512").unwrap();
+        assert_eq!(
+            opts,
+            OpenOptions::new()
+                .path("data/app")
+                .statement_cache_capacity(512)
+        );
+
+        let opts =
+            OpenOptions::from_dsn("crossdb://cache?mode=memory&db=cache&readonly=1").unwrap();
+        assert_eq!(
+            opts,
+            OpenOptions::new()
+                .memory("cache")
+                .database("cache")
+                .read_only(true)
+        );
+
+        let opts = OpenOptions::from_dsn("crossdb://data/app?create=false").unwrap();
+        assert_eq!(
+            opts,
+            OpenOptions::new().path("data/app").create_if_missing(false)
+        );
+
+        let opts = OpenOptions::from_dsn("crossdb://my%20data/app?db=my%5Fapp").unwrap();
+        assert_eq!(
+            opts,
+            OpenOptions::new().path("my data/app").database("my_app")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_dsn() {
+        for dsn in [
+            "sqlite://data/app",
+            "crossdb://data/app?cache=0",
+            "crossdb://data/app?cache=abc",
+            "crossdb://data/app?mode=tape",
+            "crossdb://data/app?sync=async",
+            "crossdb://data/app?unknown=1",
+            "crossdb://data/app?readonly",
+            "crossdb://?mode=disk",
+            "crossdb://data/app%2",
+            "crossdb://data/app%zz",
+            "crossdb://data/app%ff",
+        ] {
+            assert!(
+                matches!(OpenOptions::from_dsn(dsn), Err(Error::InvalidDsn(_))),
+                "{dsn}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_validation() {
+        assert!(check_identifier("app_1").is_ok());
+        for name in ["", "1app", "my app", "app;DROP", "a-b"] {
+            assert!(
+                matches!(check_identifier(name), Err(Error::Open(_))),
+                "{name}"
+            );
+        }
+        let err = OpenOptions::new().memory("x; DROP").open().unwrap_err();
+        assert!(matches!(err, Error::Open(_)));
+        let err = OpenOptions::new().database("x; DROP").open().unwrap_err();
+        assert!(matches!(err, Error::Open(_)));
+
+        let err = OpenOptions::new()
+            .path("no/such/database")
+            .create_if_missing(false)
+            .open()
+            .unwrap_err();
+        assert!(matches!(err, Error::Open(_)));
+    }
+}
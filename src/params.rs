@@ -1,64 +1,207 @@
 use crate::*;
+use cidr::IpInet;
+use mac_address::MacAddress;
+use std::collections::{BTreeMap, HashMap};
 
+/// A statement parameter, covering every column type the engine can bind.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
+    Null,
+    Bool(bool),
     Int(i32),
     Int64(i64),
+    UInt(u32),
+    UInt64(u64),
     Float(f32),
     Double(f64),
+    /// Same representation as [`crate::Value::Timestamp`].
+    Timestamp(i64),
     String(CString),
+    Binary(Vec<u8>),
+    Inet(IpInet),
+    Mac(MacAddress),
+    /// A JSON document, bound as its text.
+    Json(CString),
 }
 
-trait IntoValue {
-    fn into_value(self) -> Result<Value>;
+/// Conversion of a Rust value into a statement parameter.
+///
+/// Implement it to bind domain types directly:
+///
+/// ```
+/// use crossdb::{ParamValue, Result, ToParam};
+///
+/// struct UserId(i64);
+///
+/// impl ToParam for UserId {
+///     fn to_param(&self) -> Result<ParamValue> {
+///         self.0.to_param()
+///     }
+/// }
+/// ```
+pub trait ToParam {
+    fn to_param(&self) -> Result<Value>;
 }
 
 macro_rules! impl_value {
     ($t: ty, $v: ident) => {
-        impl IntoValue for $t {
-            fn into_value(self) -> Result<Value> {
-                Ok(Value::$v(self as _))
+        impl ToParam for $t {
+            fn to_param(&self) -> Result<Value> {
+                Ok(Value::$v(*self as _))
             }
         }
     };
 }
+impl_value!(bool, Bool);
 impl_value!(i8, Int);
 impl_value!(u8, Int);
 impl_value!(i16, Int);
 impl_value!(u16, Int);
 impl_value!(i32, Int);
-impl_value!(u32, Int);
-impl_value!(u64, Int64);
+impl_value!(u32, UInt);
+impl_value!(u64, UInt64);
 impl_value!(i64, Int64);
 impl_value!(f32, Float);
 impl_value!(f64, Double);
 
-impl IntoValue for String {
-    fn into_value(self) -> Result<Value> {
-        Ok(Value::String(CString::new(self)?))
+impl ToParam for [u8] {
+    fn to_param(&self) -> Result<Value> {
+        Ok(Value::Binary(self.to_vec()))
+    }
+}
+
+impl ToParam for Vec<u8> {
+    fn to_param(&self) -> Result<Value> {
+        self.as_slice().to_param()
+    }
+}
+
+impl<const N: usize> ToParam for [u8; N] {
+    fn to_param(&self) -> Result<Value> {
+        self.as_slice().to_param()
     }
 }
 
-impl IntoValue for &str {
-    fn into_value(self) -> Result<Value> {
+impl ToParam for IpInet {
+    fn to_param(&self) -> Result<Value> {
+        Ok(Value::Inet(*self))
+    }
+}
+
+impl ToParam for MacAddress {
+    fn to_param(&self) -> Result<Value> {
+        Ok(Value::Mac(*self))
+    }
+}
+
+// Values read from a result bind back losslessly.
+impl ToParam for crate::Value<'_> {
+    fn to_param(&self) -> Result<Value> {
+        use crate::Value as V;
+        Ok(match *self {
+            V::Null => Value::Null,
+            V::I8(v) => Value::Int(v.into()),
+            V::I16(v) => Value::Int(v.into()),
+            V::I32(v) => Value::Int(v),
+            V::I64(v) => Value::Int64(v),
+            V::U8(v) => Value::Int(v.into()),
+            V::U16(v) => Value::Int(v.into()),
+            V::U32(v) => Value::UInt(v),
+            V::U64(v) => Value::UInt64(v),
+            V::F32(v) => Value::Float(v),
+            V::F64(v) => Value::Double(v),
+            V::Timestamp(v) => Value::Timestamp(v),
+            V::String(v) => return v.to_param(),
+            V::Binary(v) => return v.to_param(),
+            V::Bool(v) => Value::Bool(v),
+            V::Inet(v) => Value::Inet(v),
+            V::Mac(v) => Value::Mac(v),
+            V::Json(v) => Value::Json(CString::new(v)?),
+        })
+    }
+}
+
+impl ToParam for crate::OwnedValue {
+    fn to_param(&self) -> Result<Value> {
+        self.as_value().to_param()
+    }
+}
+
+pub(crate) fn to_xdb_inet(inet: &IpInet) -> xdb_inet_t {
+    let mut addr = [0; 16];
+    let family = match inet {
+        IpInet::V4(v) => {
+            addr[..4].copy_from_slice(&v.address().octets());
+            4
+        }
+        IpInet::V6(v) => {
+            addr.copy_from_slice(&v.address().octets());
+            6
+        }
+    };
+    xdb_inet_t {
+        family,
+        mask: inet.network_length(),
+        addr,
+    }
+}
+
+impl ToParam for str {
+    fn to_param(&self) -> Result<Value> {
         Ok(Value::String(CString::new(self)?))
     }
 }
 
-impl IntoValue for CString {
-    fn into_value(self) -> Result<Value> {
-        Ok(Value::String(self))
+impl ToParam for String {
+    fn to_param(&self) -> Result<Value> {
+        self.as_str().to_param()
     }
 }
 
-impl IntoValue for Value {
-    fn into_value(self) -> Result<Value> {
-        Ok(self)
+impl ToParam for CStr {
+    fn to_param(&self) -> Result<Value> {
+        Ok(Value::String(self.to_owned()))
+    }
+}
+
+impl ToParam for CString {
+    fn to_param(&self) -> Result<Value> {
+        Ok(Value::String(self.clone()))
+    }
+}
+
+impl ToParam for Value {
+    fn to_param(&self) -> Result<Value> {
+        Ok(self.clone())
+    }
+}
+
+impl<T: ToParam> ToParam for Option<T> {
+    fn to_param(&self) -> Result<Value> {
+        match self {
+            Some(v) => v.to_param(),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+impl<T: ToParam + ?Sized> ToParam for &T {
+    fn to_param(&self) -> Result<Value> {
+        (**self).to_param()
+    }
+}
+
+impl<T: ToParam + ?Sized> ToParam for Box<T> {
+    fn to_param(&self) -> Result<Value> {
+        (**self).to_param()
     }
 }
 
 pub enum Params {
     Empty,
     Positional(Vec<Value>),
+    /// Values by placeholder name, with or without the `:`, `@` or `$` prefix.
+    Named(Vec<(String, Value)>),
 }
 
 pub trait IntoParams {
@@ -77,34 +220,138 @@ impl IntoParams for Params {
     }
 }
 
-impl<T: IntoValue> IntoParams for Vec<T> {
+impl<T: ToParam> IntoParams for Vec<T> {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<T: ToParam> IntoParams for &[T] {
     fn into_params(self) -> Result<Params> {
         let mut params = Vec::with_capacity(self.len());
         for param in self {
-            params.push(param.into_value()?);
+            params.push(param.to_param()?);
         }
         Ok(Params::Positional(params))
     }
 }
 
-impl<T: IntoValue + Clone> IntoParams for &[T] {
+impl<T: ToParam, const N: usize> IntoParams for &[T; N] {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<T: ToParam, const N: usize> IntoParams for [T; N] {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam> IntoParams for &[(K, V)] {
     fn into_params(self) -> Result<Params> {
-        self.to_vec().into_params()
+        let mut params = Vec::with_capacity(self.len());
+        for (name, param) in self {
+            params.push((name.as_ref().to_string(), param.to_param()?));
+        }
+        Ok(Params::Named(params))
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam> IntoParams for Vec<(K, V)> {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam, const N: usize> IntoParams for &[(K, V); N] {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam, const N: usize> IntoParams for [(K, V); N] {
+    fn into_params(self) -> Result<Params> {
+        self.as_slice().into_params()
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam, S> IntoParams for &HashMap<K, V, S> {
+    fn into_params(self) -> Result<Params> {
+        let mut params = Vec::with_capacity(self.len());
+        for (name, param) in self {
+            params.push((name.as_ref().to_string(), param.to_param()?));
+        }
+        Ok(Params::Named(params))
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam, S> IntoParams for HashMap<K, V, S> {
+    fn into_params(self) -> Result<Params> {
+        (&self).into_params()
+    }
+}
+
+impl<K: AsRef<str>, V: ToParam> IntoParams for &BTreeMap<K, V> {
+    fn into_params(self) -> Result<Params> {
+        let mut params = Vec::with_capacity(self.len());
+        for (name, param) in self {
+            params.push((name.as_ref().to_string(), param.to_param()?));
+        }
+        Ok(Params::Named(params))
     }
 }
 
-impl<T: IntoValue + Clone, const N: usize> IntoParams for &[T; N] {
+impl<K: AsRef<str>, V: ToParam> IntoParams for BTreeMap<K, V> {
     fn into_params(self) -> Result<Params> {
-        self.to_vec().into_params()
+        (&self).into_params()
     }
 }
 
+/// Builds named parameters of mixed types.
+///
+/// ```no_run
+/// # let mut conn = crossdb::Connection::open_with_memory()?;
+/// conn.prepare("INSERT INTO users (id, name) values (:id, :name);")?
+///     .execute(crossdb::named_params! { ":id": 1, ":name": "Alex" })?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[macro_export]
+macro_rules! named_params {
+    () => {
+        &[] as &[(&str, &dyn $crate::ToParam)]
+    };
+    ($($name:literal: $param:expr),+ $(,)?) => {
+        &[$(($name, &$param as &dyn $crate::ToParam)),+] as &[(&str, &dyn $crate::ToParam)]
+    };
+}
+
+/// Builds positional parameters of any length and of mixed types.
+///
+/// ```no_run
+/// # let mut conn = crossdb::Connection::open_with_memory()?;
+/// let name = String::from("Alex");
+/// let age: Option<i8> = None;
+/// conn.prepare("INSERT INTO users (id, name, age) values (?, ?, ?);")?
+///     .execute(crossdb::params![1, name, age])?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[macro_export]
+macro_rules! params {
+    () => {
+        &[] as &[&dyn $crate::ToParam]
+    };
+    ($($param:expr),+ $(,)?) => {
+        &[$(&$param as &dyn $crate::ToParam),+] as &[&dyn $crate::ToParam]
+    };
+}
+
 // Copy from:https://github.com/tursodatabase/libsql/blob/main/libsql/src/params.rs#L206-L207
 macro_rules! tuple_into_params {
     ($count:literal : $(($field:tt $ftype:ident)),* $(,)?) => {
-        impl<$($ftype,)*> IntoParams for ($($ftype,)*) where $($ftype: IntoValue,)* {
+        impl<$($ftype,)*> IntoParams for ($($ftype,)*) where $($ftype: ToParam,)* {
             fn into_params(self) -> Result<Params> {
-                let params = Params::Positional(vec![$(self.$field.into_value()?),*]);
+                let params = Params::Positional(vec![$(self.$field.to_param()?),*]);
                 Ok(params)
             }
         }
@@ -126,3 +373,88 @@ tuple_into_params!(13: (0 A), (1 B), (2 C), (3 D), (4 E), (5 F), (6 G), (7 H), (
 tuple_into_params!(14: (0 A), (1 B), (2 C), (3 D), (4 E), (5 F), (6 G), (7 H), (8 I), (9 J), (10 K), (11 L), (12 M), (13 N));
 tuple_into_params!(15: (0 A), (1 B), (2 C), (3 D), (4 E), (5 F), (6 G), (7 H), (8 I), (9 J), (10 K), (11 L), (12 M), (13 N), (14 O));
 tuple_into_params!(16: (0 A), (1 B), (2 C), (3 D), (4 E), (5 F), (6 G), (7 H), (8 I), (9 J), (10 K), (11 L), (12 M), (13 N), (14 O), (15 P));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positional(params: impl IntoParams) -> Vec<Value> {
+        match params.into_params().unwrap() {
+            Params::Positional(params) => params,
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_to_param() {
+        let name = String::from("Alex");
+        let s = |v: &str| Value::String(CString::new(v).unwrap());
+        assert_eq!(
+            positional((1, &name, Some(2.5), None::<i32>, "x")),
+            vec![
+                Value::Int(1),
+                s("Alex"),
+                Value::Double(2.5),
+                Value::Null,
+                s("x")
+            ]
+        );
+        assert_eq!(positional(vec!["a", "b"]), vec![s("a"), s("b")]);
+        assert_eq!(
+            positional([1_i64, 2].as_slice()),
+            vec![Value::Int64(1), Value::Int64(2)]
+        );
+        assert!("a\0b".to_param().is_err());
+    }
+
+    #[test]
+    fn test_to_param_types() {
+        let inet: IpInet = "10.0.0.1/8".parse().unwrap();
+        let mac = MacAddress::new([1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            positional((true, u32::MAX, u64::MAX, [1_u8, 2].as_slice(), inet, mac)),
+            vec![
+                Value::Bool(true),
+                Value::UInt(u32::MAX),
+                Value::UInt64(u64::MAX),
+                Value::Binary(vec![1, 2]),
+                Value::Inet(inet),
+                Value::Mac(mac),
+            ]
+        );
+
+        let xdb = to_xdb_inet(&inet);
+        assert_eq!((xdb.family, xdb.mask), (4, 8));
+        assert_eq!(xdb.addr[..4], [10, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_read_value_to_param() {
+        use crate::Value as V;
+        let inet: IpInet = "::1/128".parse().unwrap();
+        for (read, param) in [
+            (V::Null, Value::Null),
+            (V::I64(-1), Value::Int64(-1)),
+            (V::U64(u64::MAX), Value::UInt64(u64::MAX)),
+            (V::Timestamp(1), Value::Timestamp(1)),
+            (V::String("a"), Value::String(CString::new("a").unwrap())),
+            (V::Binary(&[0, 1]), Value::Binary(vec![0, 1])),
+            (V::Inet(inet), Value::Inet(inet)),
+        ] {
+            assert_eq!(read.to_param().unwrap(), param);
+        }
+    }
+
+    #[test]
+    fn test_params_macro() {
+        assert!(positional(params![]).is_empty());
+
+        let name = "Alex";
+        let params = positional(params![
+            1, name, None::<u8>, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17
+        ]);
+        assert_eq!(params.len(), 17);
+        assert_eq!(params[2], Value::Null);
+        assert_eq!(params[16], Value::Int(17));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::*;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A thread-safe pool of [`Connection`]s opened with the same [`OpenOptions`].
+///
+/// Connections are returned to the pool when their [`PooledConnection`] guard is
+/// dropped, so each keeps its prepared statement cache warm across checkouts.
+///
+/// ```no_run
+/// use crossdb::{OpenOptions, Pool};
+/// use std::time::Duration;
+///
+/// let pool = Pool::builder()
+///     .max_size(8)
+///     .connection_timeout(Duration::from_secs(5))
+///     .build(OpenOptions::new().path("data/app"))?;
+///
+/// let conn = pool.get()?;
+/// conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    options: OpenOptions,
+    config: PoolBuilder,
+    state: Mutex<Slots>,
+    available: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Slots {
+    idle: VecDeque<IdleConnection>,
+    total: usize,
+}
+
+#[derive(Debug)]
+struct IdleConnection {
+    conn: Connection,
+    since: Instant,
+}
+
+/// Snapshot of the pool's connection counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolState {
+    pub connections: usize,
+    pub idle_connections: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolBuilder {
+    max_size: usize,
+    min_idle: usize,
+    idle_timeout: Option<Duration>,
+    connection_timeout: Duration,
+    test_on_check_out: bool,
+}
+
+impl Default for PoolBuilder {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            min_idle: 0,
+            idle_timeout: Some(Duration::from_secs(600)),
+            connection_timeout: Duration::from_secs(30),
+            test_on_check_out: true,
+        }
+    }
+}
+
+impl PoolBuilder {
+    /// Maximum number of connections, defaults to 10.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Number of idle connections kept open regardless of `idle_timeout`, defaults to 0.
+    /// They are opened eagerly by [`PoolBuilder::build`].
+    pub fn min_idle(mut self, min_idle: usize) -> Self {
+        self.min_idle = min_idle;
+        self
+    }
+
+    /// Idle connections above `min_idle` are closed after this long, defaults to 10 minutes.
+    pub fn idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// How long [`Pool::get`] waits for a free connection, defaults to 30 seconds.
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = timeout;
+        self
+    }
+
+    /// Whether to health-check idle connections before handing them out, defaults to `true`.
+    pub fn test_on_check_out(mut self, test: bool) -> Self {
+        self.test_on_check_out = test;
+        self
+    }
+
+    pub fn build(self, options: OpenOptions) -> Result<Pool> {
+        if self.max_size == 0 || self.min_idle > self.max_size {
+            return Err(Error::Pool(
+                "max_size must be non-zero and at least min_idle".into(),
+            ));
+        }
+        let pool = Pool {
+            inner: Arc::new(PoolInner {
+                options,
+                config: self,
+                state: Mutex::new(Slots::default()),
+                available: Condvar::new(),
+            }),
+        };
+        for _ in 0..self.min_idle {
+            let conn = pool.inner.options.open()?;
+            let mut state = pool.inner.lock();
+            state.total += 1;
+            state.idle.push_back(IdleConnection {
+                conn,
+                since: Instant::now(),
+            });
+        }
+        Ok(pool)
+    }
+}
+
+impl Pool {
+    pub fn new(options: OpenOptions) -> Result<Self> {
+        Self::builder().build(options)
+    }
+
+    pub fn builder() -> PoolBuilder {
+        PoolBuilder::default()
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.inner.config.max_size
+    }
+
+    pub fn state(&self) -> PoolState {
+        let state = self.inner.lock();
+        PoolState {
+            connections: state.total,
+            idle_connections: state.idle.len(),
+        }
+    }
+
+    /// Checks out a connection, waiting up to the configured connection timeout.
+    pub fn get(&self) -> Result<PooledConnection> {
+        self.get_timeout(self.inner.config.connection_timeout)
+    }
+
+    pub fn get_timeout(&self, timeout: Duration) -> Result<PooledConnection> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.inner.lock();
+        loop {
+            let expired = self.inner.reap(&mut state);
+            if !expired.is_empty() {
+                drop(state);
+                drop(expired);
+                state = self.inner.lock();
+            }
+            if let Some(idle) = state.idle.pop_back() {
+                drop(state);
+                if !self.inner.config.test_on_check_out || is_healthy(&idle.conn) {
+                    return Ok(self.guard(idle.conn));
+                }
+                drop(idle);
+                state = self.inner.lock();
+                state.total -= 1;
+                continue;
+            }
+            if state.total < self.inner.config.max_size {
+                state.total += 1;
+                drop(state);
+                return match self.inner.options.open() {
+                    Ok(conn) => Ok(self.guard(conn)),
+                    Err(err) => {
+                        self.inner.lock().total -= 1;
+                        self.inner.available.notify_one();
+                        Err(err)
+                    }
+                };
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::PoolTimeout);
+            }
+            state = self
+                .inner
+                .available
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn guard(&self, conn: Connection) -> PooledConnection {
+        PooledConnection {
+            pool: self.inner.clone(),
+            conn: Some(conn),
+        }
+    }
+}
+
+impl PoolInner {
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Takes out connections that have been idle for too long, oldest first. They
+    // are returned so the caller can close them once the lock is released.
+    fn reap(&self, state: &mut Slots) -> Vec<IdleConnection> {
+        let mut expired = Vec::new();
+        let Some(timeout) = self.config.idle_timeout else {
+            return expired;
+        };
+        while state.idle.len() > self.config.min_idle {
+            match state.idle.front() {
+                Some(idle) if idle.since.elapsed() >= timeout => {
+                    expired.extend(state.idle.pop_front());
+                    state.total -= 1;
+                }
+                _ => break,
+            }
+        }
+        expired
+    }
+
+    fn put(&self, conn: Connection) {
+        // A transaction left open, e.g. by a raw `begin`, is rolled back before the
+        // connection is reused, and the connection is closed if that fails.
+        let conn = match conn.transaction_depth() {
+            0 => Some(conn),
+            _ => conn.reset_transaction().is_ok().then_some(conn),
+        };
+        let mut state = self.lock();
+        let expired = self.reap(&mut state);
+        match conn {
+            Some(conn) => state.idle.push_back(IdleConnection {
+                conn,
+                since: Instant::now(),
+            }),
+            None => state.total -= 1,
+        }
+        drop(state);
+        self.available.notify_one();
+        drop(expired);
+    }
+}
+
+fn is_healthy(conn: &Connection) -> bool {
+    conn.transaction_depth() == 0 && conn.current_database().is_ok()
+}
+
+/// A connection checked out from a [`Pool`], returned to it on drop.
+#[derive(Debug)]
+pub struct PooledConnection {
+    pool: Arc<PoolInner>,
+    conn: Option<Connection>,
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.put(conn);
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool() {
+        let pool = Pool::builder()
+            .max_size(2)
+            .build(OpenOptions::new().memory("pool"))
+            .unwrap();
+        assert_eq!(pool.state().connections, 0);
+
+        let mut conn = pool.get().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+        conn.prepare("INSERT INTO t (id) values (?);")
+            .unwrap()
+            .execute((1,))
+            .unwrap();
+        let other = pool.get().unwrap();
+        assert_eq!(
+            pool.state(),
+            PoolState {
+                connections: 2,
+                idle_connections: 0
+            }
+        );
+        assert!(matches!(
+            pool.get_timeout(Duration::from_millis(10)),
+            Err(Error::PoolTimeout)
+        ));
+
+        drop(other);
+        drop(conn);
+        assert_eq!(pool.state().idle_connections, 2);
+
+        let conn = pool.get().unwrap();
+        assert_eq!(conn.query("SELECT * FROM t;").unwrap().row_count(), 1);
+    }
+
+    #[test]
+    fn test_pool_threads() {
+        let pool = Pool::builder()
+            .max_size(4)
+            .min_idle(1)
+            .build(OpenOptions::new().memory("pool_threads"))
+            .unwrap();
+        pool.get()
+            .unwrap()
+            .execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|id| {
+                let pool = pool.clone();
+                std::thread::spawn(move || {
+                    let mut conn = pool.get().unwrap();
+                    let stmt = conn.prepare("INSERT INTO t (id) values (?);").unwrap();
+                    stmt.execute((id,)).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(pool.state().connections <= 4);
+        let conn = pool.get().unwrap();
+        assert_eq!(conn.query("SELECT * FROM t;").unwrap().row_count(), 16);
+    }
+
+    #[test]
+    fn test_open_transaction_on_return() {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(OpenOptions::new().memory("pool_tx"))
+            .unwrap();
+        let conn = pool.get().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+        conn.begin().unwrap();
+        conn.execute("INSERT INTO t (id) values (1);").unwrap();
+        drop(conn);
+
+        let conn = pool.get().unwrap();
+        assert_eq!(conn.transaction_depth(), 0);
+        assert_eq!(conn.query("SELECT * FROM t;").unwrap().row_count(), 0);
+    }
+
+    #[test]
+    fn test_idle_timeout() {
+        let pool = Pool::builder()
+            .idle_timeout(Some(Duration::ZERO))
+            .build(OpenOptions::new().memory("pool_idle"))
+            .unwrap();
+        drop(pool.get().unwrap());
+        assert_eq!(pool.state().idle_connections, 1);
+        drop(pool.get().unwrap());
+        assert_eq!(pool.state().connections, 1);
+    }
+}
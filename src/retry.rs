@@ -0,0 +1,105 @@
+use crate::*;
+use std::time::Duration;
+
+/// Decides whether and when [`Connection::transaction_with_retry`] runs a failed
+/// transaction again.
+pub trait RetryPolicy {
+    /// Called after attempt number `attempt` (starting at 1) failed with a retryable error.
+    /// Returns the delay before the next attempt, or `None` to give up.
+    fn next_delay(&mut self, attempt: u32, err: &Error) -> Option<Duration>;
+
+    /// Whether a failed transaction is worth running again. Nothing is by default,
+    /// since CrossDB waits on locks instead of failing and has no conflict error code,
+    /// so a policy that retries has to pick the errors itself.
+    fn is_retryable(&self, _err: &Error) -> bool {
+        false
+    }
+}
+
+/// Never retries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn next_delay(&mut self, _attempt: u32, _err: &Error) -> Option<Duration> {
+        None
+    }
+}
+
+/// Exponential backoff, by default 5 attempts starting at 10ms and capped at 1s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    max_attempts: u32,
+    initial: Duration,
+    max: Duration,
+    multiplier: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+            multiplier: 2,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max = delay;
+        self
+    }
+
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+impl RetryPolicy for Backoff {
+    fn next_delay(&mut self, attempt: u32, _err: &Error) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let factor = self.multiplier.saturating_pow(attempt - 1);
+        Some(self.initial.saturating_mul(factor).min(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let err = Error::Query(xdb_errno_e_XDB_ERROR, String::new());
+        let mut backoff = Backoff::new()
+            .max_attempts(4)
+            .initial_delay(Duration::from_millis(10))
+            .max_delay(Duration::from_millis(30));
+        assert_eq!(backoff.next_delay(1, &err), Some(Duration::from_millis(10)));
+        assert_eq!(backoff.next_delay(2, &err), Some(Duration::from_millis(20)));
+        assert_eq!(backoff.next_delay(3, &err), Some(Duration::from_millis(30)));
+        assert_eq!(backoff.next_delay(4, &err), None);
+
+        assert_eq!(NoRetry.next_delay(1, &err), None);
+        // Retrying has to be opted into per error.
+        assert!(!backoff.is_retryable(&err));
+    }
+}
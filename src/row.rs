@@ -1,5 +1,6 @@
-use crate::{de::RowDeserializer, Columns, Result, Value};
-use serde::de::{value::Error as DeError, DeserializeOwned};
+use crate::{de::RowDeserializer, Columns, Error, FromValue, OwnedValue, Result, Value};
+use serde::de::{value::Error as DeError, Deserialize, DeserializeOwned};
+use serde::{Serialize, Serializer};
 use std::fmt::Debug;
 
 pub struct Row<'a> {
@@ -13,7 +14,7 @@ impl Debug for Row<'_> {
     }
 }
 
-impl Row<'_> {
+impl<'a> Row<'a> {
     pub fn columns(&self) -> Columns {
         self.columns.clone()
     }
@@ -36,9 +37,160 @@ impl Row<'_> {
         }
     }
 
+    /// Converts a value with [`FromValue`], naming the column and its type on failure.
+    pub fn get_as<'i, T: FromValue<'a>>(&self, index: impl IntoValueIndex<'i>) -> Result<T> {
+        let i = match index.into_index() {
+            ValueIndex::ColumnName(name) => self
+                .columns
+                .iter()
+                .position(|c| c.name() == name)
+                .ok_or_else(|| Error::ColumnNotFound(format!("'{name}'")))?,
+            ValueIndex::ColumnIndex(i) if i < self.values.len() => i,
+            ValueIndex::ColumnIndex(i) => return Err(Error::ColumnNotFound(i.to_string())),
+        };
+        T::from_value(&self.values[i]).map_err(|err| Error::Column {
+            name: self.columns.name(i).to_string(),
+            datatype: self.columns.datatype(i),
+            source: Box::new(err),
+        })
+    }
+
+    /// Deserializes the row into a struct or map keyed by column name, a tuple
+    /// or sequence of its values, or, for a single column, its value.
     pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DeError> {
+        self.deserialize_with(false)
+    }
+
+    /// Like [`Row::deserialize`] but matches column names to struct fields ignoring
+    /// ASCII case, so `SELECT ID, Name` fills `id` and `name`. Fields of a
+    /// `#[serde(flatten)]` struct are still matched exactly.
+    pub fn deserialize_case_insensitive<T: DeserializeOwned>(&self) -> Result<T, DeError> {
+        self.deserialize_with(true)
+    }
+
+    /// Deserializes the row without copying, `&str` and `&[u8]` fields borrow the
+    /// query's result buffer.
+    ///
+    /// The result keeps the [`Query`](crate::Query) borrowed, so it has to be
+    /// dropped before the next row is fetched.
+    ///
+    /// ```no_run
+    /// #[derive(serde::Deserialize)]
+    /// struct User<'a> {
+    ///     id: i32,
+    ///     name: &'a str,
+    /// }
+    ///
+    /// let conn = crossdb::Connection::open_with_memory()?;
+    /// let mut query = conn.query("SELECT id, name FROM users;")?;
+    /// let mut rows = query.rows();
+    /// while let Some(row) = rows.next()? {
+    ///     let user: User = row.deserialize_borrowed()?;
+    ///     println!("{}: {}", user.id, user.name);
+    /// }
+    /// # Ok::<(), crossdb::Error>(())
+    /// ```
+    ///
+    /// ```compile_fail
+    /// let conn = crossdb::Connection::open_with_memory().unwrap();
+    /// let mut query = conn.query("SELECT name FROM users;").unwrap();
+    /// let name: &str = query.fetch_row().unwrap().unwrap().deserialize_borrowed().unwrap();
+    /// query.fetch_row().unwrap();
+    /// println!("{name}");
+    /// ```
+    pub fn deserialize_borrowed<T: Deserialize<'a>>(&self) -> Result<T, DeError> {
         T::deserialize(RowDeserializer::new(self))
     }
+
+    pub(crate) fn deserialize_with<T: DeserializeOwned>(
+        &self,
+        case_insensitive: bool,
+    ) -> Result<T, DeError> {
+        T::deserialize(RowDeserializer::new(self).case_insensitive(case_insensitive))
+    }
+
+    /// Copies the row out of the result buffer, see [`OwnedRow`].
+    pub fn to_owned(&self) -> OwnedRow {
+        OwnedRow {
+            columns: self.columns.clone(),
+            values: self.values.iter().map(|v| v.into_owned()).collect(),
+        }
+    }
+}
+
+/// A [`Row`] that owns its values, so it outlives the [`Query`](crate::Query)
+/// and is `Send + Sync`. Created by [`Row::to_owned`] and
+/// [`Query::collect_owned`](crate::Query::collect_owned).
+#[derive(Clone)]
+pub struct OwnedRow {
+    columns: Columns,
+    values: Vec<OwnedValue>,
+}
+
+impl Debug for OwnedRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OwnedRow").field(&self.values).finish()
+    }
+}
+
+impl OwnedRow {
+    pub fn columns(&self) -> Columns {
+        self.columns.clone()
+    }
+
+    pub fn values(&self) -> &[OwnedValue] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<OwnedValue> {
+        self.values
+    }
+
+    pub fn get<'i>(&self, index: impl IntoValueIndex<'i>) -> &OwnedValue {
+        self.try_get(index).expect("Row index out of bounds")
+    }
+
+    pub fn try_get<'i>(&self, index: impl IntoValueIndex<'i>) -> Option<&OwnedValue> {
+        match index.into_index() {
+            ValueIndex::ColumnName(name) => {
+                let i = self.columns.iter().position(|c| c.name() == name)?;
+                self.values.get(i)
+            }
+            ValueIndex::ColumnIndex(i) => self.values.get(i),
+        }
+    }
+
+    /// Borrows the row, to read it with the [`Row`] accessors.
+    pub fn as_row(&self) -> Row<'_> {
+        Row {
+            columns: self.columns.clone(),
+            values: self.values.iter().map(|v| v.as_value()).collect(),
+        }
+    }
+
+    /// See [`Row::get_as`].
+    pub fn get_as<'a, 'i, T: FromValue<'a>>(&'a self, index: impl IntoValueIndex<'i>) -> Result<T> {
+        self.as_row().get_as(index)
+    }
+
+    /// See [`Row::deserialize`].
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DeError> {
+        self.as_row().deserialize()
+    }
+}
+
+/// Serializes as a map keyed by column name, serialize [`Row::values`] for a
+/// sequence instead.
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.columns.iter().map(|c| c.name()).zip(&self.values))
+    }
+}
+
+impl Serialize for OwnedRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.columns.iter().map(|c| c.name()).zip(&self.values))
+    }
 }
 
 pub enum ValueIndex<'i> {
@@ -67,3 +219,127 @@ impl<'i> IntoValueIndex<'i> for ValueIndex<'i> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Column, DataType, ParamValue, ToParam};
+
+    #[test]
+    fn test_get_as() {
+        let columns = Columns::new(vec![
+            Column::new("id".into(), DataType::Int),
+            Column::new("age".into(), DataType::SmallInt),
+            Column::new("name".into(), DataType::VChar),
+        ]);
+        let row = Row {
+            columns,
+            values: vec![Value::I32(1), Value::I16(300), Value::Null],
+        };
+
+        assert_eq!(row.get_as::<u8>(0).unwrap(), 1);
+        assert_eq!(row.get_as::<i16>("age").unwrap(), 300);
+        assert_eq!(row.get_as::<Option<&str>>("name").unwrap(), None);
+
+        let err = row.get_as::<u8>("age").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Column 'age' (SMALLINT): Conversion error: value 300 out of range for u8"
+        );
+        assert!(matches!(
+            row.get_as::<String>("name"),
+            Err(Error::Column { .. })
+        ));
+        assert!(matches!(
+            row.get_as::<i32>("missing"),
+            Err(Error::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            row.get_as::<i32>(3),
+            Err(Error::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_borrowed() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct User<'a> {
+            name: &'a str,
+            avatar: &'a [u8],
+        }
+
+        let buf = String::from("Alex");
+        let user = {
+            let row = Row {
+                columns: Columns::new(vec![
+                    Column::new("name".into(), DataType::VChar),
+                    Column::new("avatar".into(), DataType::VBinary),
+                ]),
+                values: vec![Value::String(&buf), Value::Binary(buf.as_bytes())],
+            };
+            row.deserialize_borrowed::<User>().unwrap()
+        };
+        assert_eq!(user.name, "Alex");
+        assert!(std::ptr::eq(user.name, buf.as_str()));
+        assert!(std::ptr::eq(user.avatar, buf.as_bytes()));
+
+        let row = Row {
+            columns: Columns::new(vec![Column::new("name".into(), DataType::VChar)]),
+            values: vec![Value::I32(1)],
+        };
+        assert!(row.deserialize_borrowed::<&str>().is_err());
+    }
+
+    #[test]
+    fn test_to_owned() {
+        fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+        let owned = {
+            let name = String::from("Alex");
+            let row = Row {
+                columns: Columns::new(vec![
+                    Column::new("id".into(), DataType::Int),
+                    Column::new("name".into(), DataType::VChar),
+                ]),
+                values: vec![Value::I32(1), Value::String(&name)],
+            };
+            row.to_owned()
+        };
+        assert_send_sync(&owned);
+        assert_eq!(owned.get("name"), &OwnedValue::String("Alex".into()));
+        assert_eq!(owned.get_as::<&str>(1).unwrap(), "Alex");
+        assert_eq!(
+            owned.deserialize::<(i32, String)>().unwrap(),
+            (1, "Alex".into())
+        );
+        assert_eq!(owned.try_get("missing"), None);
+
+        let values = std::thread::spawn(move || owned.into_values())
+            .join()
+            .unwrap();
+        assert_eq!(values[0].to_param().unwrap(), ParamValue::Int(1));
+    }
+
+    #[test]
+    fn test_serialize() {
+        use serde_json::{json, to_value};
+
+        let row = Row {
+            columns: Columns::new(vec![
+                Column::new("id".into(), DataType::Int),
+                Column::new("name".into(), DataType::VChar),
+            ]),
+            values: vec![Value::I32(1), Value::Null],
+        };
+        assert_eq!(to_value(&row).unwrap(), json!({"id": 1, "name": null}));
+        assert_eq!(
+            to_value(row.to_owned()).unwrap(),
+            json!({"id": 1, "name": null})
+        );
+        assert_eq!(to_value(row.values()).unwrap(), json!([1, null]));
+        assert_eq!(
+            to_value(row.columns()).unwrap(),
+            json!([{"name": "id", "type": "INT"}, {"name": "name", "type": "VCHAR"}])
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::de::ValueDeserializer;
+use crate::*;
+use serde::de::Error as _;
+use serde::ser::SerializeSeq;
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::iter::FusedIterator;
+
+/// A streaming iterator over the rows of a [`Query`], returned by [`Query::rows`].
+///
+/// Each [`Row`] borrows the iterator, so it has to be dropped before the next call.
+///
+/// ```no_run
+/// # let conn = crossdb::Connection::open_with_memory()?;
+/// let mut query = conn.query("SELECT * FROM users;")?;
+/// let mut rows = query.rows();
+/// while let Some(row) = rows.next()? {
+///     println!("{}", row.get("name"));
+/// }
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Rows<'q, 'conn> {
+    query: &'q mut Query<'conn>,
+}
+
+impl Rows<'_, '_> {
+    // A lending iterator, `Iterator` cannot express rows borrowing the iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<Row<'_>>> {
+        self.query.fetch_row()
+    }
+}
+
+/// Iterator returned by [`Query::map_rows`] and [`Statement::query_map`].
+pub struct MappedRows<'conn, F> {
+    query: Query<'conn>,
+    f: F,
+}
+
+impl<T, F> Iterator for MappedRows<'_, F>
+where
+    F: FnMut(&Row<'_>) -> Result<T>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.query.fetch_row() {
+            Ok(row) => row.map(|row| (self.f)(&row)),
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+impl<T, F> FusedIterator for MappedRows<'_, F> where F: FnMut(&Row<'_>) -> Result<T> {}
+
+/// Iterator returned by [`Query::into_iter_as`] and [`Statement::query_as`],
+/// deserializing one row per call.
+pub struct IntoIterAs<'conn, T> {
+    query: Query<'conn>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Iterator for IntoIterAs<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.query.fetch_row_as()
+    }
+}
+
+impl<T: DeserializeOwned> FusedIterator for IntoIterAs<'_, T> {}
+
+/// Serializes the rows of a query as a sequence of maps keyed by column name,
+/// returned by [`Query::into_serialize`].
+///
+/// ```no_run
+/// let conn = crossdb::Connection::open_with_memory()?;
+/// let query = conn.query("SELECT * FROM users;")?;
+/// let json = serde_json::to_string(&query.into_serialize())?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+///
+/// Rows are fetched while serializing, so only the first serialization sees them.
+#[derive(Debug)]
+pub struct SerializeRows<'conn> {
+    query: RefCell<Query<'conn>>,
+}
+
+impl Serialize for SerializeRows<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut query = self.query.borrow_mut();
+        let remaining = query.row_count().saturating_sub(query.fetched);
+        let mut seq = serializer.serialize_seq(Some(remaining))?;
+        while let Some(row) = query.fetch_row().map_err(serde::ser::Error::custom)? {
+            seq.serialize_element(&row)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'conn> Query<'conn> {
+    /// Streams the remaining rows into any serde format, see [`SerializeRows`].
+    pub fn into_serialize(self) -> SerializeRows<'conn> {
+        SerializeRows {
+            query: RefCell::new(self),
+        }
+    }
+
+    pub fn rows(&mut self) -> Rows<'_, 'conn> {
+        Rows { query: self }
+    }
+
+    /// Lazily maps each row with `f`.
+    pub fn map_rows<T, F>(self, f: F) -> MappedRows<'conn, F>
+    where
+        F: FnMut(&Row<'_>) -> Result<T>,
+    {
+        MappedRows { query: self, f }
+    }
+
+    /// Lazily deserializes each row, unlike [`Query::fetch_rows_as`] which collects them.
+    pub fn into_iter_as<T: DeserializeOwned>(self) -> IntoIterAs<'conn, T> {
+        IntoIterAs {
+            query: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Query<'_> {
+    // Maps the only row of the result, `None` if it is empty.
+    fn single<T>(mut self, f: impl FnOnce(&Row<'_>) -> Result<T>) -> Result<Option<T>> {
+        let value = match self.fetch_row()? {
+            Some(row) => f(&row)?,
+            None => return Ok(None),
+        };
+        match self.fetch_row()? {
+            Some(_) => Err(Error::TooManyRows),
+            None => Ok(Some(value)),
+        }
+    }
+
+    pub(crate) fn one_as<T: DeserializeOwned>(self) -> Result<T> {
+        self.opt_as()?.ok_or(Error::NoRows)
+    }
+
+    pub(crate) fn opt_as<T: DeserializeOwned>(self) -> Result<Option<T>> {
+        let case_insensitive = self.case_insensitive;
+        self.single(|row| Ok(row.deserialize_with(case_insensitive)?))
+    }
+
+    pub(crate) fn scalar<T: DeserializeOwned>(self) -> Result<T> {
+        self.single(|row| {
+            let value = row
+                .values()
+                .first()
+                .ok_or_else(|| DeError::custom("Expects at least one column"))?;
+            Ok(T::deserialize(ValueDeserializer(*value))?)
+        })?
+        .ok_or(Error::NoRows)
+    }
+}
+
+impl Connection {
+    /// Deserializes the only row of the result.
+    ///
+    /// Fails with [`Error::NoRows`] if the result is empty and
+    /// [`Error::TooManyRows`] if it has more than one row.
+    pub fn query_one<T: DeserializeOwned, S: AsRef<str>>(&self, sql: S) -> Result<T> {
+        self.query(sql)?.one_as()
+    }
+
+    /// Like [`Connection::query_one`] but returns `None` for an empty result.
+    pub fn query_opt<T: DeserializeOwned, S: AsRef<str>>(&self, sql: S) -> Result<Option<T>> {
+        self.query(sql)?.opt_as()
+    }
+
+    /// Deserializes the first column of the only row, e.g. for `SELECT COUNT(*)`.
+    pub fn query_scalar<T: DeserializeOwned, S: AsRef<str>>(&self, sql: S) -> Result<T> {
+        self.query(sql)?.scalar()
+    }
+}
+
+impl Statement {
+    pub fn query_one<T: DeserializeOwned>(&mut self, params: impl IntoParams) -> Result<T> {
+        self.query(params)?.one_as()
+    }
+
+    pub fn query_opt<T: DeserializeOwned>(&mut self, params: impl IntoParams) -> Result<Option<T>> {
+        self.query(params)?.opt_as()
+    }
+
+    pub fn query_scalar<T: DeserializeOwned>(&mut self, params: impl IntoParams) -> Result<T> {
+        self.query(params)?.scalar()
+    }
+
+    pub fn query_map<T, F>(&mut self, params: impl IntoParams, f: F) -> Result<MappedRows<'_, F>>
+    where
+        F: FnMut(&Row<'_>) -> Result<T>,
+    {
+        self.query(params).map(|q| q.map_rows(f))
+    }
+
+    pub fn query_as<T: DeserializeOwned>(
+        &mut self,
+        params: impl IntoParams,
+    ) -> Result<IntoIterAs<'_, T>> {
+        self.query(params).map(|q| q.into_iter_as())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct User {
+        id: i32,
+        name: String,
+    }
+
+    fn setup(name: &str) -> Connection {
+        let mut conn = OpenOptions::new().memory(name).open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS users(id INT, name VARCHAR(255));")
+            .unwrap();
+        let stmt = conn
+            .prepare("INSERT INTO users (id, name) values (?, ?);")
+            .unwrap();
+        stmt.execute((1, "Alex")).unwrap();
+        stmt.execute((2, "Thorne")).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_rows() {
+        let conn = setup("rows_rows");
+        let mut query = conn.query("SELECT * FROM users;").unwrap();
+        let mut rows = query.rows();
+        let mut names = vec![];
+        while let Some(row) = rows.next().unwrap() {
+            names.push(row.get("name").to_string());
+        }
+        assert_eq!(names, vec!["Alex", "Thorne"]);
+    }
+
+    #[test]
+    fn test_map_rows() {
+        let mut conn = setup("rows_map_rows");
+        let ids = conn
+            .query("SELECT * FROM users;")
+            .unwrap()
+            .map_rows(|row| Ok(row.get(0).to_string()))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(ids, vec!["1", "2"]);
+
+        let names = conn
+            .prepare("SELECT * FROM users WHERE id = ?;")
+            .unwrap()
+            .query_map((2,), |row| Ok(row.get("name").to_string()))
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(names, vec!["Thorne"]);
+    }
+
+    #[test]
+    fn test_into_iter_as() {
+        let mut conn = setup("rows_into_iter_as");
+        let mut users = conn
+            .query("SELECT * FROM users;")
+            .unwrap()
+            .into_iter_as::<User>();
+        assert_eq!(
+            users.next().unwrap().unwrap(),
+            User {
+                id: 1,
+                name: "Alex".into()
+            }
+        );
+        assert_eq!(users.count(), 1);
+
+        let users = conn
+            .prepare("SELECT * FROM users WHERE id = ?;")
+            .unwrap()
+            .query_as::<User>((1,))
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(users.len(), 1);
+    }
+
+    #[test]
+    fn test_query_one() {
+        let mut conn = setup("rows_query_one");
+        let user: User = conn.query_one("SELECT * FROM users WHERE id = 1;").unwrap();
+        assert_eq!(user.name, "Alex");
+        assert!(matches!(
+            conn.query_one::<User, _>("SELECT * FROM users;"),
+            Err(Error::TooManyRows)
+        ));
+        assert!(matches!(
+            conn.query_one::<User, _>("SELECT * FROM users WHERE id = 3;"),
+            Err(Error::NoRows)
+        ));
+
+        let user: Option<User> = conn.query_opt("SELECT * FROM users WHERE id = 3;").unwrap();
+        assert_eq!(user, None);
+        let user: Option<User> = conn.query_opt("SELECT * FROM users WHERE id = 2;").unwrap();
+        assert_eq!(user.unwrap().name, "Thorne");
+
+        let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM users;").unwrap();
+        assert_eq!(count, 2);
+
+        let stmt = conn
+            .prepare("SELECT name FROM users WHERE id = ?;")
+            .unwrap();
+        let name: String = stmt.query_scalar((1,)).unwrap();
+        assert_eq!(name, "Alex");
+        assert!(matches!(
+            stmt.query_scalar::<String>((3,)),
+            Err(Error::NoRows)
+        ));
+        assert!(matches!(
+            stmt.query_one::<(String,)>((3,)),
+            Err(Error::NoRows)
+        ));
+    }
+
+    #[test]
+    fn test_into_serialize() {
+        let conn = setup("rows_into_serialize");
+        let mut query = conn.query("SELECT * FROM users ORDER BY id;").unwrap();
+        query.fetch_row().unwrap();
+        let rows = serde_json::to_value(query.into_serialize()).unwrap();
+        assert_eq!(rows, serde_json::json!([{"id": 2, "name": "Thorne"}]));
+    }
+}
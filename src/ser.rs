@@ -0,0 +1,611 @@
+use crate::*;
+use serde::ser::{
+    self, Impossible, Serialize, SerializeMap, SerializeStruct, SerializeStructVariant,
+};
+
+/// Serializes a struct (or map) into named [`Params`], one per field.
+///
+/// ```
+/// #[derive(serde::Serialize)]
+/// struct User<'a> {
+///     id: i32,
+///     name: &'a str,
+///     age: Option<i8>,
+/// }
+///
+/// let params = crossdb::to_params(&User { id: 1, name: "Alex", age: None })?;
+/// # Ok::<(), crossdb::Error>(())
+/// ```
+pub fn to_params<T: Serialize + ?Sized>(value: &T) -> Result<Params> {
+    to_named_params(value).map(Params::Named)
+}
+
+fn to_named_params<T: Serialize + ?Sized>(value: &T) -> Result<Vec<(String, ParamValue)>> {
+    value.serialize(ParamsSerializer)
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Serialize(msg.to_string())
+    }
+}
+
+struct ParamsSerializer;
+
+macro_rules! unsupported {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok> {
+                Err(Error::Serialize(format!(
+                    "{} is not supported here",
+                    &stringify!($method)["serialize_".len()..]
+                )))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ParamsSerializer {
+    type Ok = Vec<(String, ParamValue)>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = NamedSerializer;
+    type SerializeStruct = NamedSerializer;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    unsupported! {
+        serialize_bool(bool), serialize_i8(i8), serialize_i16(i16), serialize_i32(i32),
+        serialize_i64(i64), serialize_u8(u8), serialize_u16(u16), serialize_u32(u32),
+        serialize_u64(u64), serialize_f32(f32), serialize_f64(f64), serialize_char(char),
+        serialize_str(&str), serialize_bytes(&[u8]), serialize_none(), serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(NamedSerializer::new(len.unwrap_or_default()))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(NamedSerializer::new(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Error::Serialize("expected a struct or map".into()))
+    }
+}
+
+struct NamedSerializer {
+    params: Vec<(String, ParamValue)>,
+    key: Option<String>,
+}
+
+impl NamedSerializer {
+    fn new(len: usize) -> Self {
+        Self {
+            params: Vec::with_capacity(len),
+            key: None,
+        }
+    }
+}
+
+impl SerializeStruct for NamedSerializer {
+    type Ok = Vec<(String, ParamValue)>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let value = value.serialize(ValueSerializer)?;
+        self.params.push((key.to_string(), value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.params)
+    }
+}
+
+impl SerializeMap for NamedSerializer {
+    type Ok = Vec<(String, ParamValue)>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(ValueSerializer)? {
+            ParamValue::String(key) => {
+                self.key = Some(key.into_string().map_err(|e| e.utf8_error())?);
+                Ok(())
+            }
+            _ => Err(Error::Serialize("map keys must be strings".into())),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().ok_or_else(|| {
+            <Error as ser::Error>::custom("serialize_value called before serialize_key")
+        })?;
+        self.params.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.params)
+    }
+}
+
+/// Serializes a single field into a [`ParamValue`].
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = ParamValue;
+    type Error = Error;
+    type SerializeSeq = Impossible<ParamValue, Error>;
+    type SerializeTuple = Impossible<ParamValue, Error>;
+    type SerializeTupleStruct = Impossible<ParamValue, Error>;
+    type SerializeTupleVariant = Impossible<ParamValue, Error>;
+    type SerializeMap = JsonSerializer<JsonMap>;
+    type SerializeStruct = JsonSerializer<JsonMap>;
+    type SerializeStructVariant = JsonSerializer<JsonStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<ParamValue> {
+        i64::try_from(v)
+            .map_err(|_| Error::Serialize(format!("{v} out of range for BIGINT")))?
+            .to_param()
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<ParamValue> {
+        u64::try_from(v)
+            .map_err(|_| Error::Serialize(format!("{v} out of range for UBIGINT")))?
+            .to_param()
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_char(self, v: char) -> Result<ParamValue> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<ParamValue> {
+        v.to_param()
+    }
+
+    fn serialize_none(self) -> Result<ParamValue> {
+        Ok(ParamValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<ParamValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<ParamValue> {
+        Ok(ParamValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ParamValue> {
+        Ok(ParamValue::Null)
+    }
+
+    // Fieldless enums are stored by variant name.
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<ParamValue> {
+        self.serialize_str(variant)
+    }
+
+    // Fields serialized with `crate::timestamp` are bound as TIMESTAMP.
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<ParamValue> {
+        match value.serialize(self)? {
+            ParamValue::Int64(v) if name == timestamp::NEWTYPE => Ok(ParamValue::Timestamp(v)),
+            value => Ok(value),
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<ParamValue> {
+        let value =
+            serde_json::value::Serializer.serialize_newtype_variant(name, index, variant, value)?;
+        value.to_param()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported_value("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported_value("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported_value(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported_value(variant))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(JsonSerializer {
+            inner: serde_json::value::Serializer.serialize_map(len)?,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(JsonSerializer {
+            inner: serde_json::value::Serializer.serialize_struct(name, len)?,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(JsonSerializer {
+            inner: serde_json::value::Serializer
+                .serialize_struct_variant(name, index, variant, len)?,
+        })
+    }
+}
+
+type JsonMap = <serde_json::value::Serializer as ser::Serializer>::SerializeMap;
+type JsonStructVariant = <serde_json::value::Serializer as ser::Serializer>::SerializeStructVariant;
+
+/// Nested maps and structs are bound as JSON documents.
+struct JsonSerializer<S> {
+    inner: S,
+}
+
+impl SerializeMap for JsonSerializer<JsonMap> {
+    type Ok = ParamValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        Ok(self.inner.serialize_key(key)?)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        Ok(self.inner.serialize_value(value)?)
+    }
+
+    fn end(self) -> Result<ParamValue> {
+        SerializeMap::end(self.inner)?.to_param()
+    }
+}
+
+impl SerializeStruct for JsonSerializer<JsonMap> {
+    type Ok = ParamValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        Ok(SerializeStruct::serialize_field(
+            &mut self.inner,
+            key,
+            value,
+        )?)
+    }
+
+    fn end(self) -> Result<ParamValue> {
+        SerializeStruct::end(self.inner)?.to_param()
+    }
+}
+
+impl SerializeStructVariant for JsonSerializer<JsonStructVariant> {
+    type Ok = ParamValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        Ok(self.inner.serialize_field(key, value)?)
+    }
+
+    fn end(self) -> Result<ParamValue> {
+        self.inner.end()?.to_param()
+    }
+}
+
+fn unsupported_value(kind: &str) -> Error {
+    Error::Serialize(format!("cannot bind {kind} as a column value"))
+}
+
+impl Statement {
+    /// Binds the fields of `value` to the placeholders of the same name.
+    pub fn execute_struct<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<u64> {
+        self.execute(to_params(value)?)
+    }
+
+    pub fn query_struct<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<Query<'_>> {
+        self.query(to_params(value)?)
+    }
+}
+
+impl Connection {
+    /// Inserts `value` into `table`, using its field names as the column list.
+    ///
+    /// The table (optionally `database.table`) and field names are spliced into
+    /// the statement, so anything but plain identifiers fails with
+    /// [`Error::InvalidIdentifier`].
+    pub fn insert<T: Serialize + ?Sized>(&mut self, table: &str, value: &T) -> Result<u64> {
+        if table.matches('.').count() > 1 || !table.split('.').all(options::is_identifier) {
+            return Err(Error::InvalidIdentifier(table.to_string()));
+        }
+        let params = to_named_params(value)?;
+        if let Some((name, _)) = params
+            .iter()
+            .find(|(name, _)| !options::is_identifier(name))
+        {
+            return Err(Error::InvalidIdentifier(name.clone()));
+        }
+        let columns: Vec<&str> = params.iter().map(|(name, _)| name.as_str()).collect();
+        let placeholders: Vec<String> = columns.iter().map(|c| format!(":{c}")).collect();
+        let sql = format!(
+            "INSERT INTO {table} ({}) values ({});",
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        self.prepare(sql)?.execute(Params::Named(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Guest,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        id: i32,
+        #[serde(rename = "name")]
+        user_name: String,
+        age: Option<i8>,
+        role: Role,
+    }
+
+    fn named(params: Params) -> Vec<(String, ParamValue)> {
+        match params {
+            Params::Named(params) => params,
+            _ => panic!("expected named params"),
+        }
+    }
+
+    #[test]
+    fn test_to_params() {
+        let user = User {
+            id: 1,
+            user_name: "Alex".into(),
+            age: None,
+            role: Role::Admin,
+        };
+        let s = |v: &str| ParamValue::String(CString::new(v).unwrap());
+        assert_eq!(
+            named(to_params(&user).unwrap()),
+            vec![
+                ("id".into(), ParamValue::Int(1)),
+                ("name".into(), s("Alex")),
+                ("age".into(), ParamValue::Null),
+                ("role".into(), s("admin")),
+            ]
+        );
+
+        let map = BTreeMap::from([("a", 1_u64), ("b", u64::MAX)]);
+        assert_eq!(
+            named(to_params(&map).unwrap()),
+            vec![
+                ("a".into(), ParamValue::UInt64(1)),
+                ("b".into(), ParamValue::UInt64(u64::MAX)),
+            ]
+        );
+
+        #[derive(Serialize)]
+        struct Doc {
+            meta: BTreeMap<&'static str, i32>,
+            role: Option<Role>,
+        }
+        let doc = Doc {
+            meta: BTreeMap::from([("a", 1)]),
+            role: None,
+        };
+        assert_eq!(
+            named(to_params(&doc).unwrap()),
+            vec![
+                (
+                    "meta".into(),
+                    ParamValue::Json(CString::new(r#"{"a":1}"#).unwrap())
+                ),
+                ("role".into(), ParamValue::Null),
+            ]
+        );
+
+        assert!(matches!(to_params(&1), Err(Error::Serialize(_))));
+        assert!(matches!(
+            to_params(&BTreeMap::from([("a", vec![1])])),
+            Err(Error::Serialize(_))
+        ));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut conn = OpenOptions::new().memory("ser_insert").open().unwrap();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS users(id INT, name VARCHAR(255), age TINYINT, role VARCHAR(16));",
+        )
+        .unwrap();
+
+        let alex = User {
+            id: 1,
+            user_name: "Alex".into(),
+            age: Some(18),
+            role: Role::Admin,
+        };
+        assert_eq!(conn.insert("users", &alex).unwrap(), 1);
+
+        let stmt = conn
+            .prepare("INSERT INTO users (id, name, age, role) values (:id, :name, :age, :role);")
+            .unwrap();
+        let thorne = User {
+            id: 2,
+            user_name: "Thorne".into(),
+            age: None,
+            role: Role::Guest,
+        };
+        stmt.execute_struct(&thorne).unwrap();
+
+        let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM users;").unwrap();
+        assert_eq!(count, 2);
+
+        assert!(matches!(
+            conn.insert("users; DROP TABLE users", &alex),
+            Err(Error::InvalidIdentifier(name)) if name == "users; DROP TABLE users"
+        ));
+        let map = BTreeMap::from([("id", 3), ("age) values (1); --", 1)]);
+        assert!(matches!(
+            conn.insert("users", &map),
+            Err(Error::InvalidIdentifier(name)) if name == "age) values (1); --"
+        ));
+        let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM users;").unwrap();
+        assert_eq!(count, 2);
+    }
+}
@@ -2,10 +2,19 @@ use params::Params;
 
 use crate::*;
 
+/// A prepared statement cached by its [`Connection`].
+///
+/// Binding parameters mutates engine state, so execution requires `&mut self`.
 pub struct Statement {
     pub(crate) ptr: *mut xdb_stmt_t,
+    // Placeholder name of each `?` position, `None` for positional ones.
+    names: Vec<Option<String>>,
 }
 
+// SAFETY: the statement handle is owned by a single connection and only
+// bound or executed through `&mut self`.
+unsafe impl Send for Statement {}
+
 impl Drop for Statement {
     fn drop(&mut self) {
         unsafe {
@@ -15,23 +24,95 @@ impl Drop for Statement {
 }
 
 impl Statement {
-    pub fn query(&self, params: impl IntoParams) -> Result<Query> {
+    /// Prepares `sql`, rewriting `:name`, `@name` and `$name` placeholders to `?`.
+    pub(crate) fn prepare(conn: *mut xdb_conn_t, sql: &str) -> Result<Self> {
+        let (sql, names) = parse_placeholders(sql);
+        let sql = CString::new(sql)?;
+        let ptr = unsafe { xdb_stmt_prepare(conn, sql.as_ptr()) };
+        // The engine reports no reason for a failed prepare, and running the SQL
+        // again to get one could apply it.
+        if ptr.is_null() {
+            let msg = format!("failed to prepare '{}'", sql.to_string_lossy());
+            return Err(Error::Query(xdb_errno_e_XDB_E_STMT, msg));
+        }
+        Ok(Self { ptr, names })
+    }
+
+    pub fn parameter_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Name of the placeholder at `index` (starting at 0), without its prefix.
+    pub fn parameter_name(&self, index: usize) -> Option<&str> {
+        self.names.get(index)?.as_deref()
+    }
+
+    // Orders named parameters by position.
+    fn resolve_named<'p>(&self, params: &'p [(String, ParamValue)]) -> Result<Vec<&'p ParamValue>> {
+        let find = |name: &str| params.iter().find(|(n, _)| strip_prefix(n) == name);
+        for (i, (name, _)) in params.iter().enumerate() {
+            let name = strip_prefix(name);
+            if !self.names.iter().any(|n| n.as_deref() == Some(name)) {
+                return Err(Error::UnknownParam(name.to_string()));
+            }
+            if params[..i].iter().any(|(n, _)| strip_prefix(n) == name) {
+                return Err(Error::DuplicateParam(name.to_string()));
+            }
+        }
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match name {
+                Some(name) => find(name)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| Error::MissingParam(name.clone())),
+                None => Err(Error::MissingParam(format!("?{}", i + 1))),
+            })
+            .collect()
+    }
+
+    pub fn query(&mut self, params: impl IntoParams) -> Result<Query<'_>> {
         unsafe {
             let params = params.into_params()?;
             // Here we use the &params
             // Ensure that 'ParamValue::String' is not released before 'xdb_stmt_exec'.
-            if let Params::Positional(params) = &params {
+            let params = match &params {
+                Params::Empty => None,
+                Params::Positional(params) => Some(params.iter().collect::<Vec<_>>()),
+                Params::Named(params) => Some(self.resolve_named(params)?),
+            };
+            if let Some(params) = params {
                 self.clear_bindings()?;
+                // Same for the converted INET/MAC values, the capacity is reserved
+                // up front so pushing never moves the already bound ones.
+                let mut inets = Vec::with_capacity(params.len());
+                let mut macs = Vec::with_capacity(params.len());
                 for (i, p) in params.iter().enumerate() {
                     let i = i as u16 + 1;
                     let ret = match p {
+                        ParamValue::Null => xdb_bind_null(self.ptr, i),
+                        ParamValue::Bool(v) => xdb_bind_bool(self.ptr, i, *v),
                         ParamValue::Int(v) => xdb_bind_int(self.ptr, i, *v),
                         ParamValue::Int64(v) => xdb_bind_int64(self.ptr, i, *v),
+                        ParamValue::UInt(v) => xdb_bind_int64(self.ptr, i, *v as i64),
+                        ParamValue::UInt64(v) => xdb_bind_uint64(self.ptr, i, *v),
                         ParamValue::Float(v) => xdb_bind_float(self.ptr, i, *v),
                         ParamValue::Double(v) => xdb_bind_double(self.ptr, i, *v),
-                        ParamValue::String(v) => {
+                        ParamValue::Timestamp(v) => xdb_bind_timestamp(self.ptr, i, *v),
+                        ParamValue::String(v) | ParamValue::Json(v) => {
                             xdb_bind_str2(self.ptr, i, v.as_ptr(), v.as_bytes().len() as i32)
                         }
+                        ParamValue::Binary(v) => {
+                            xdb_bind_blob(self.ptr, i, v.as_ptr() as _, v.len() as i32)
+                        }
+                        ParamValue::Inet(v) => {
+                            inets.push(params::to_xdb_inet(v));
+                            xdb_bind_inet(self.ptr, i, inets.last_mut().unwrap())
+                        }
+                        ParamValue::Mac(v) => {
+                            macs.push(xdb_mac_t { addr: v.bytes() });
+                            xdb_bind_mac(self.ptr, i, macs.last_mut().unwrap())
+                        }
                     };
                     if ret != 0 {
                         return Err(Error::BindParams);
@@ -43,11 +124,11 @@ impl Statement {
         }
     }
 
-    pub fn execute(&self, params: impl IntoParams) -> Result<u64> {
+    pub fn execute(&mut self, params: impl IntoParams) -> Result<u64> {
         self.query(params).map(|q| q.affected_rows())
     }
 
-    pub fn clear_bindings(&self) -> Result<()> {
+    pub fn clear_bindings(&mut self) -> Result<()> {
         let ret = unsafe { xdb_clear_bindings(self.ptr) };
         match ret {
             0 => Ok(()),
@@ -55,3 +136,139 @@ impl Statement {
         }
     }
 }
+
+fn strip_prefix(name: &str) -> &str {
+    name.strip_prefix([':', '@', '$']).unwrap_or(name)
+}
+
+/// Rewrites named placeholders to `?`, returning the name of every position.
+/// Quoted strings, identifiers and comments are left untouched.
+fn parse_placeholders(sql: &str) -> (String, Vec<Option<String>>) {
+    let bytes = sql.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut out = String::with_capacity(sql.len());
+    let mut names = vec![];
+    let mut last = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            q @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != q {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'?' => names.push(None),
+            // A `::` cast, e.g. `x::INT`.
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 1,
+            b':' | b'@' | b'$'
+                if bytes
+                    .get(i + 1)
+                    .is_some_and(|&b| b.is_ascii_alphabetic() || b == b'_') =>
+            {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && is_ident(bytes[end]) {
+                    end += 1;
+                }
+                out.push_str(&sql[last..i]);
+                out.push('?');
+                names.push(Some(sql[start..end].to_string()));
+                last = end;
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    out.push_str(&sql[last.min(sql.len())..]);
+    (out, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_placeholders() {
+        let (sql, names) = parse_placeholders(
+            "SELECT * FROM t WHERE a = :a AND b = @b_1 AND c = $c AND d = ? AND e = ':x' -- @y\n",
+        );
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE a = ? AND b = ? AND c = ? AND d = ? AND e = ':x' -- @y\n"
+        );
+        assert_eq!(
+            names,
+            vec![Some("a".into()), Some("b_1".into()), Some("c".into()), None]
+        );
+
+        let (sql, names) = parse_placeholders("SELECT * FROM t WHERE a = :a OR b = :a");
+        assert_eq!(sql, "SELECT * FROM t WHERE a = ? OR b = ?");
+        assert_eq!(names, vec![Some("a".into()), Some("a".into())]);
+
+        let (sql, names) = parse_placeholders("SELECT a::INT FROM t WHERE b = :b::BIGINT");
+        assert_eq!(sql, "SELECT a::INT FROM t WHERE b = ?::BIGINT");
+        assert_eq!(names, vec![Some("b".into())]);
+    }
+
+    #[test]
+    fn test_named_params() {
+        let mut conn = OpenOptions::new().memory("named_params").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT, name VARCHAR(255));")
+            .unwrap();
+
+        let stmt = conn
+            .prepare("INSERT INTO t (id, name) values (:id, @name);")
+            .unwrap();
+        assert_eq!(stmt.parameter_count(), 2);
+        assert_eq!(stmt.parameter_name(1), Some("name"));
+        stmt.execute(&[(":id", &1 as &dyn ToParam), ("@name", &"Alex")][..])
+            .unwrap();
+        stmt.execute(named_params! { "name": "Thorne", "id": 2 })
+            .unwrap();
+        let map = HashMap::from([("id", ParamValue::Int(3)), ("name", ParamValue::Null)]);
+        stmt.execute(&map).unwrap();
+
+        assert!(matches!(
+            stmt.execute(named_params! { ":id": 4 }),
+            Err(Error::MissingParam(name)) if name == "name"
+        ));
+        assert!(matches!(
+            stmt.execute(named_params! { ":id": 4, ":name": "a", ":age": 1 }),
+            Err(Error::UnknownParam(name)) if name == "age"
+        ));
+        assert!(matches!(
+            stmt.execute(named_params! { ":id": 4, ":name": "a", "@id": 5 }),
+            Err(Error::DuplicateParam(name)) if name == "id"
+        ));
+
+        let name: String = conn
+            .prepare("SELECT name FROM t WHERE id = $id;")
+            .unwrap()
+            .query_scalar(named_params! { "$id": 2 })
+            .unwrap();
+        assert_eq!(name, "Thorne");
+
+        // A statement that fails to prepare is not run.
+        assert!(matches!(
+            conn.prepare("SELEC name FROM t;"),
+            Err(Error::Query(code, _)) if code == xdb_errno_e_XDB_E_STMT
+        ));
+        assert_eq!(conn.query("SELECT * FROM t;").unwrap().row_count(), 3);
+    }
+}
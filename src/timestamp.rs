@@ -0,0 +1,386 @@
+//! TIMESTAMP support.
+//!
+//! CrossDB stores a TIMESTAMP as a signed 64-bit count of microseconds since
+//! the Unix epoch (1970-01-01T00:00:00Z), with no time zone attached. That
+//! count is what [`Value::Timestamp`] and [`ParamValue::Timestamp`] hold.
+//!
+//! [`SystemTime`] is always supported; the `chrono`, `time` and `jiff` features
+//! add their date-time types. Values are converted at microsecond precision,
+//! so anything finer is truncated towards the past when binding. Naive types
+//! (`chrono::NaiveDateTime`, `time::PrimitiveDateTime`) are taken to be UTC.
+//!
+//! Through serde, each of these types keeps its own form (a string, a struct
+//! or a tuple), so struct fields should use `#[serde(with = "crossdb::timestamp")]`
+//! to be bound as a TIMESTAMP by [`to_params`] or [`Connection::insert`] and to
+//! be read back from one:
+//!
+//! ```
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Event {
+//!     id: i32,
+//!     #[serde(with = "crossdb::timestamp")]
+//!     at: std::time::SystemTime,
+//! }
+//!
+//! let event = Event { id: 1, at: std::time::UNIX_EPOCH };
+//! let params = crossdb::to_params(&event)?;
+//! # Ok::<(), crossdb::Error>(())
+//! ```
+
+use crate::from_value::{invalid_type, out_of_range};
+use crate::*;
+use serde::{de, ser, Deserialize, Deserializer, Serializer};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Newtype name `serialize` wraps the microseconds in, so the parameter
+// serializer can tell them from a plain integer.
+pub(crate) const NEWTYPE: &str = "$crossdb::Timestamp";
+
+/// Serializes a date-time as its TIMESTAMP microseconds.
+///
+/// Parameters are bound as [`ParamValue::Timestamp`], other serializers see an `i64`.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ToParam + ?Sized,
+    S: Serializer,
+{
+    match value.to_param().map_err(ser::Error::custom)? {
+        ParamValue::Timestamp(v) => serializer.serialize_newtype_struct(NEWTYPE, &v),
+        _ => Err(ser::Error::custom("expected a TIMESTAMP value")),
+    }
+}
+
+/// Deserializes a date-time from TIMESTAMP microseconds.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: for<'a> FromValue<'a>,
+    D: Deserializer<'de>,
+{
+    let v = i64::deserialize(deserializer)?;
+    T::from_value(&Value::Timestamp(v)).map_err(de::Error::custom)
+}
+
+fn timestamp<T>(value: &Value<'_>) -> Result<i64> {
+    match *value {
+        Value::Timestamp(v) | Value::I64(v) => Ok(v),
+        _ => Err(invalid_type::<T>(value)),
+    }
+}
+
+/// Formats a TIMESTAMP as RFC 3339 in UTC, e.g. `2024-05-01T12:30:00.000000Z`.
+pub(crate) fn to_rfc3339(micros: i64) -> String {
+    let secs = micros.div_euclid(1_000_000);
+    let micros = micros.rem_euclid(1_000_000);
+    let (days, secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Days to civil date, from Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{micros:06}Z",
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+pub(crate) fn from_system_time(time: SystemTime) -> Result<i64> {
+    let micros = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => i64::try_from(d.as_micros()).ok(),
+        Err(e) => {
+            let d = e.duration();
+            // Round away from the epoch so sub-microsecond times truncate to the past.
+            let micros = d.as_micros() + u128::from(d.subsec_nanos() % 1_000 != 0);
+            i64::try_from(micros).ok().map(|v| -v)
+        }
+    };
+    micros.ok_or_else(|| Error::FromValue(format!("{time:?} out of range for TIMESTAMP")))
+}
+
+pub(crate) fn to_system_time(micros: i64) -> Result<SystemTime> {
+    let d = Duration::from_micros(micros.unsigned_abs());
+    let time = if micros >= 0 {
+        UNIX_EPOCH.checked_add(d)
+    } else {
+        UNIX_EPOCH.checked_sub(d)
+    };
+    time.ok_or_else(|| out_of_range::<SystemTime>(micros))
+}
+
+impl ToParam for SystemTime {
+    fn to_param(&self) -> Result<ParamValue> {
+        from_system_time(*self).map(ParamValue::Timestamp)
+    }
+}
+
+impl FromValue<'_> for SystemTime {
+    fn from_value(value: &Value<'_>) -> Result<Self> {
+        to_system_time(timestamp::<Self>(value)?)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use super::*;
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+    impl<Tz: TimeZone> ToParam for DateTime<Tz> {
+        fn to_param(&self) -> Result<ParamValue> {
+            Ok(ParamValue::Timestamp(self.timestamp_micros()))
+        }
+    }
+
+    impl ToParam for NaiveDateTime {
+        fn to_param(&self) -> Result<ParamValue> {
+            self.and_utc().to_param()
+        }
+    }
+
+    impl FromValue<'_> for DateTime<Utc> {
+        fn from_value(value: &Value<'_>) -> Result<Self> {
+            let v = timestamp::<Self>(value)?;
+            DateTime::from_timestamp_micros(v).ok_or_else(|| out_of_range::<Self>(v))
+        }
+    }
+
+    impl FromValue<'_> for NaiveDateTime {
+        fn from_value(value: &Value<'_>) -> Result<Self> {
+            DateTime::<Utc>::from_value(value).map(|v| v.naive_utc())
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use super::*;
+    use time::{OffsetDateTime, PrimitiveDateTime};
+
+    impl ToParam for OffsetDateTime {
+        fn to_param(&self) -> Result<ParamValue> {
+            let micros = self.unix_timestamp_nanos().div_euclid(1_000);
+            i64::try_from(micros)
+                .map(ParamValue::Timestamp)
+                .map_err(|_| Error::FromValue(format!("{self} out of range for TIMESTAMP")))
+        }
+    }
+
+    impl ToParam for PrimitiveDateTime {
+        fn to_param(&self) -> Result<ParamValue> {
+            self.assume_utc().to_param()
+        }
+    }
+
+    impl FromValue<'_> for OffsetDateTime {
+        fn from_value(value: &Value<'_>) -> Result<Self> {
+            let v = timestamp::<Self>(value)?;
+            OffsetDateTime::from_unix_timestamp_nanos(i128::from(v) * 1_000)
+                .map_err(|_| out_of_range::<Self>(v))
+        }
+    }
+
+    impl FromValue<'_> for PrimitiveDateTime {
+        fn from_value(value: &Value<'_>) -> Result<Self> {
+            OffsetDateTime::from_value(value).map(|v| PrimitiveDateTime::new(v.date(), v.time()))
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_impls {
+    use super::*;
+    use jiff::{Timestamp, Zoned};
+
+    impl ToParam for Timestamp {
+        fn to_param(&self) -> Result<ParamValue> {
+            Ok(ParamValue::Timestamp(self.as_microsecond()))
+        }
+    }
+
+    impl ToParam for Zoned {
+        fn to_param(&self) -> Result<ParamValue> {
+            self.timestamp().to_param()
+        }
+    }
+
+    impl FromValue<'_> for Timestamp {
+        fn from_value(value: &Value<'_>) -> Result<Self> {
+            let v = timestamp::<Self>(value)?;
+            Timestamp::from_microsecond(v).map_err(|_| out_of_range::<Self>(v))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rfc3339() {
+        assert_eq!(to_rfc3339(0), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(to_rfc3339(1), "1970-01-01T00:00:00.000001Z");
+        assert_eq!(to_rfc3339(-1), "1969-12-31T23:59:59.999999Z");
+        assert_eq!(
+            to_rfc3339(1_709_210_096_123_456),
+            "2024-02-29T12:34:56.123456Z"
+        );
+        assert_eq!(
+            to_rfc3339(951_782_400_000_000),
+            "2000-02-29T00:00:00.000000Z"
+        );
+        assert_eq!(
+            to_rfc3339(-2_208_988_800_000_000),
+            "1900-01-01T00:00:00.000000Z"
+        );
+    }
+
+    #[test]
+    fn test_system_time() {
+        let time = UNIX_EPOCH + Duration::from_nanos(1_500_000_999);
+        assert_eq!(time.to_param().unwrap(), ParamValue::Timestamp(1_500_000));
+        let time = UNIX_EPOCH - Duration::from_nanos(1_500);
+        assert_eq!(time.to_param().unwrap(), ParamValue::Timestamp(-2));
+
+        let time = SystemTime::from_value(&Value::Timestamp(-2)).unwrap();
+        assert_eq!(time, UNIX_EPOCH - Duration::from_micros(2));
+        let time = SystemTime::from_value(&Value::Timestamp(1_500_000)).unwrap();
+        assert_eq!(time, UNIX_EPOCH + Duration::from_micros(1_500_000));
+        assert!(SystemTime::from_value(&Value::String("now")).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+
+        let micros = 1_709_210_096_123_456;
+        let utc = DateTime::<Utc>::from_value(&Value::Timestamp(micros)).unwrap();
+        assert_eq!(utc.to_rfc3339(), "2024-02-29T12:34:56.123456+00:00");
+        assert_eq!(utc.to_param().unwrap(), ParamValue::Timestamp(micros));
+
+        let naive = NaiveDateTime::from_value(&Value::Timestamp(micros)).unwrap();
+        assert_eq!(naive, utc.naive_utc());
+        assert_eq!(naive.to_param().unwrap(), ParamValue::Timestamp(micros));
+
+        let local: DateTime<FixedOffset> = "2024-02-29T14:34:56.123456+02:00".parse().unwrap();
+        assert_eq!(local.to_param().unwrap(), ParamValue::Timestamp(micros));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+        let micros = 1_709_210_096_123_456;
+        let utc = OffsetDateTime::from_value(&Value::Timestamp(micros)).unwrap();
+        assert_eq!(utc.unix_timestamp(), 1_709_210_096);
+        assert_eq!(utc.microsecond(), 123_456);
+        assert_eq!(utc.to_param().unwrap(), ParamValue::Timestamp(micros));
+
+        let offset = utc.to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+        assert_eq!(offset.to_param().unwrap(), ParamValue::Timestamp(micros));
+
+        let naive = PrimitiveDateTime::from_value(&Value::Timestamp(micros)).unwrap();
+        assert_eq!(naive.assume_utc(), utc);
+        assert_eq!(naive.to_param().unwrap(), ParamValue::Timestamp(micros));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_jiff() {
+        use jiff::Timestamp;
+
+        let micros = 1_709_210_096_123_456;
+        let ts = Timestamp::from_value(&Value::Timestamp(micros)).unwrap();
+        assert_eq!(ts.to_string(), "2024-02-29T12:34:56.123456Z");
+        assert_eq!(ts.to_param().unwrap(), ParamValue::Timestamp(micros));
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Event {
+        id: i32,
+        #[serde(with = "crate::timestamp")]
+        at: SystemTime,
+    }
+
+    #[test]
+    fn test_serde_with() {
+        let event = Event {
+            id: 1,
+            at: UNIX_EPOCH + Duration::from_micros(1_500_000),
+        };
+        let Params::Named(params) = to_params(&event).unwrap() else {
+            panic!("expected named params");
+        };
+        assert_eq!(
+            params,
+            vec![
+                ("id".into(), ParamValue::Int(1)),
+                ("at".into(), ParamValue::Timestamp(1_500_000)),
+            ]
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json, serde_json::json!({"id": 1, "at": 1_500_000}));
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+    }
+
+    #[test]
+    fn test_timestamp_rows() {
+        let mut conn = OpenOptions::new().memory("timestamp_rows").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS events(id INT, at TIMESTAMP);")
+            .unwrap();
+        let micros: i64 = 1_709_210_096_123_456;
+        let at = UNIX_EPOCH + Duration::from_micros(micros as u64);
+        conn.insert("events", &Event { id: 1, at }).unwrap();
+
+        let event: Event = conn.query_one("SELECT * FROM events;").unwrap();
+        assert_eq!(event.at, at);
+
+        #[cfg(feature = "chrono")]
+        {
+            #[derive(serde::Deserialize)]
+            struct Row {
+                utc: chrono::DateTime<chrono::Utc>,
+                naive: chrono::NaiveDateTime,
+            }
+            let row: Row = conn
+                .query_one("SELECT at AS utc, at AS naive FROM events;")
+                .unwrap();
+            assert_eq!(row.utc.timestamp_micros(), micros);
+            assert_eq!(row.naive, row.utc.naive_utc());
+        }
+
+        #[cfg(feature = "time")]
+        {
+            #[derive(serde::Deserialize)]
+            struct Row {
+                #[serde(with = "crate::timestamp")]
+                utc: time::OffsetDateTime,
+                #[serde(with = "crate::timestamp")]
+                naive: time::PrimitiveDateTime,
+            }
+            let row: Row = conn
+                .query_one("SELECT at AS utc, at AS naive FROM events;")
+                .unwrap();
+            assert_eq!(row.utc.unix_timestamp_nanos(), i128::from(micros) * 1_000);
+            assert_eq!(row.naive.assume_utc(), row.utc);
+        }
+
+        #[cfg(feature = "jiff")]
+        {
+            #[derive(serde::Deserialize)]
+            struct Row {
+                at: jiff::Timestamp,
+            }
+            let row: Row = conn.query_one("SELECT at FROM events;").unwrap();
+            assert_eq!(row.at.as_microsecond(), micros);
+        }
+    }
+}
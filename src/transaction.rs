@@ -0,0 +1,248 @@
+use crate::*;
+use std::ops::{Deref, DerefMut};
+
+/// A transaction scope returned by [`Connection::transaction`].
+///
+/// The transaction is rolled back on drop unless [`Transaction::commit`] was called.
+/// It derefs to [`Connection`], so queries and statements run inside the transaction.
+///
+/// Calling `transaction()` on a transaction opens a nested scope. CrossDB has no
+/// savepoints, so nested scopes are emulated: committing a nested scope defers to the
+/// outermost one, while rolling it back marks the whole transaction as rollback-only
+/// and the outermost `commit` then fails with [`Error::RollbackOnly`].
+///
+/// [`Connection::begin`], [`Connection::commit`] and [`Connection::rollback`] share
+/// this bookkeeping, so calling them through the transaction opens and closes
+/// nested scopes the same way.
+#[derive(Debug)]
+pub struct Transaction<'conn> {
+    conn: &'conn mut Connection,
+    finished: bool,
+}
+
+impl<'conn> Transaction<'conn> {
+    pub(crate) fn new(conn: &'conn mut Connection) -> Result<Self> {
+        conn.begin()?;
+        Ok(Self {
+            conn,
+            finished: false,
+        })
+    }
+
+    /// Nesting depth of this scope, the outermost transaction is `1`.
+    pub fn depth(&self) -> usize {
+        self.conn.transaction_depth()
+    }
+
+    pub fn is_nested(&self) -> bool {
+        self.depth() > 1
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        self.finish(true)
+    }
+
+    pub fn rollback(mut self) -> Result<()> {
+        self.finish(false)
+    }
+
+    fn finish(&mut self, commit: bool) -> Result<()> {
+        self.finished = true;
+        match commit {
+            true => self.conn.commit(),
+            false => self.conn.rollback(),
+        }
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.finish(false);
+        }
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn
+    }
+}
+
+impl DerefMut for Transaction<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn count(conn: &Connection) -> usize {
+        conn.query("SELECT * FROM t;").unwrap().row_count()
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut conn = OpenOptions::new().memory("tx_transaction").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+
+        let tx = conn.transaction().unwrap();
+        tx.execute("INSERT INTO t (id) values (1);").unwrap();
+        tx.commit().unwrap();
+        assert_eq!(count(&conn), 1);
+
+        let tx = conn.transaction().unwrap();
+        tx.execute("INSERT INTO t (id) values (2);").unwrap();
+        tx.rollback().unwrap();
+        assert_eq!(count(&conn), 1);
+
+        {
+            let tx = conn.transaction().unwrap();
+            tx.execute("INSERT INTO t (id) values (3);").unwrap();
+        }
+        assert_eq!(count(&conn), 1);
+    }
+
+    #[test]
+    fn test_nested_transaction() {
+        let mut conn = OpenOptions::new()
+            .memory("tx_nested_transaction")
+            .open()
+            .unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+
+        let mut tx = conn.transaction().unwrap();
+        tx.execute("INSERT INTO t (id) values (1);").unwrap();
+        let inner = tx.transaction().unwrap();
+        assert_eq!(inner.depth(), 2);
+        inner.execute("INSERT INTO t (id) values (2);").unwrap();
+        inner.commit().unwrap();
+        assert_eq!(tx.depth(), 1);
+        tx.commit().unwrap();
+        assert_eq!(count(&conn), 2);
+
+        let mut tx = conn.transaction().unwrap();
+        tx.execute("INSERT INTO t (id) values (3);").unwrap();
+        tx.transaction().unwrap().rollback().unwrap();
+        assert!(matches!(tx.commit(), Err(Error::RollbackOnly)));
+        assert_eq!(count(&conn), 2);
+    }
+
+    #[test]
+    fn test_raw_transaction() {
+        let mut conn = OpenOptions::new().memory("tx_raw").open().unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+
+        conn.begin().unwrap();
+        assert_eq!(conn.transaction_depth(), 1);
+        conn.execute("INSERT INTO t (id) values (1);").unwrap();
+        conn.commit().unwrap();
+        assert_eq!(conn.transaction_depth(), 0);
+        assert_eq!(count(&conn), 1);
+
+        let tx = conn.transaction().unwrap();
+        tx.begin().unwrap();
+        assert_eq!(tx.depth(), 2);
+        tx.execute("INSERT INTO t (id) values (2);").unwrap();
+        Connection::rollback(&tx).unwrap();
+        assert_eq!(tx.depth(), 1);
+        assert!(matches!(tx.commit(), Err(Error::RollbackOnly)));
+        assert_eq!(conn.transaction_depth(), 0);
+        assert_eq!(count(&conn), 1);
+    }
+
+    #[test]
+    fn test_transaction_with() {
+        let mut conn = OpenOptions::new()
+            .memory("tx_transaction_with")
+            .open()
+            .unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+
+        let affected = conn
+            .transaction_with(|tx| tx.execute("INSERT INTO t (id) values (1);"))
+            .unwrap();
+        assert_eq!(affected, 1);
+        assert_eq!(count(&conn), 1);
+
+        let mut attempts = 0;
+        let result = conn.transaction_with(|tx| {
+            attempts += 1;
+            tx.execute("INSERT INTO t (id) values (2);")?;
+            tx.execute("INSERT INTO missing (id) values (2);")
+        });
+        assert!(matches!(result, Err(Error::Query(..))));
+        assert_eq!(attempts, 1);
+        assert_eq!(count(&conn), 1);
+    }
+
+    // Treats failures with a "conflict" message as retryable, the engine
+    // reports no conflict code of its own.
+    struct RetryConflicts(Backoff);
+
+    impl RetryPolicy for RetryConflicts {
+        fn next_delay(&mut self, attempt: u32, err: &Error) -> Option<std::time::Duration> {
+            self.0.next_delay(attempt, err)
+        }
+
+        fn is_retryable(&self, err: &Error) -> bool {
+            matches!(err, Error::Query(_, msg) if msg == "conflict")
+        }
+    }
+
+    #[test]
+    fn test_transaction_with_retry() {
+        let mut conn = OpenOptions::new()
+            .memory("tx_transaction_with_retry")
+            .open()
+            .unwrap();
+        conn.execute("CREATE TABLE IF NOT EXISTS t(id INT);")
+            .unwrap();
+        let conflict = || Error::Query(xdb_errno_e_XDB_ERROR, "conflict".into());
+
+        let mut attempts = 0;
+        let backoff = Backoff::new().initial_delay(std::time::Duration::ZERO);
+        let (_, made) = conn
+            .transaction_with_retry(&mut RetryConflicts(backoff), |tx| {
+                attempts += 1;
+                tx.execute("INSERT INTO t (id) values (1);")?;
+                match attempts {
+                    1 => Err(conflict()),
+                    _ => Ok(()),
+                }
+            })
+            .unwrap();
+        assert_eq!(attempts, 2);
+        assert_eq!(made, 2);
+        assert_eq!(count(&conn), 1);
+
+        let result = conn
+            .transaction_with_retry(&mut RetryConflicts(backoff.max_attempts(3)), |_| {
+                Err::<(), _>(conflict())
+            });
+        assert!(matches!(result, Err(Error::RetriesExhausted(3, _))));
+
+        let result = conn.transaction_with_retry(&mut NoRetry, |_| Err::<(), _>(conflict()));
+        assert!(matches!(result, Err(Error::Query(_, _))));
+
+        let result = conn.transaction_with_retry(&mut RetryConflicts(backoff), |_| Ok(7));
+        assert!(matches!(result, Ok((7, 1))));
+
+        // Backoff alone does not pick any error to retry.
+        let mut attempts = 0;
+        let result = conn.transaction_with_retry(&mut { backoff }, |_| {
+            attempts += 1;
+            Err::<(), _>(conflict())
+        });
+        assert!(matches!(result, Err(Error::Query(_, _))));
+        assert_eq!(attempts, 1);
+    }
+}
@@ -1,32 +1,48 @@
+use crate::timestamp::to_rfc3339;
 use crate::*;
 use cidr::{IpInet, Ipv4Inet, Ipv6Inet};
 use mac_address::MacAddress;
+use serde::{ser::Error as _, Serialize, Serializer};
+use std::borrow::Cow;
+use std::ffi::c_void;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Value<'a> {
     Null,
+    I8(i8),
+    I16(i16),
     I32(i32),
     I64(i64),
+    U8(u8),
+    U16(u16),
     U32(u32),
     U64(u64),
     F32(f32),
     F64(f64),
+    /// Microseconds since the Unix epoch, UTC. Converts to and from
+    /// `SystemTime`, and the `chrono`, `time` and `jiff` types when those
+    /// features are enabled.
     Timestamp(i64),
     String(&'a str),
     Binary(&'a [u8]),
     Bool(bool),
     Inet(IpInet),
     Mac(MacAddress),
-    // TODO: Array
+    /// The JSON document as stored, see [`Json`] to parse it.
+    Json(&'a str),
 }
 
 impl Display for Value<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Null => write!(f, "NULL"),
+            Value::I8(v) => write!(f, "{}", v),
+            Value::I16(v) => write!(f, "{}", v),
             Value::I32(v) => write!(f, "{}", v),
             Value::I64(v) => write!(f, "{}", v),
+            Value::U8(v) => write!(f, "{}", v),
+            Value::U16(v) => write!(f, "{}", v),
             Value::U32(v) => write!(f, "{}", v),
             Value::U64(v) => write!(f, "{}", v),
             Value::F32(v) => write!(f, "{}", v),
@@ -37,72 +53,360 @@ impl Display for Value<'_> {
             Value::Bool(v) => write!(f, "{}", v),
             Value::Inet(v) => write!(f, "{:?}", v),
             Value::Mac(v) => write!(f, "{:?}", v),
+            Value::Json(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+// TIMESTAMP is RFC 3339 text in human-readable formats such as JSON and
+// microseconds otherwise, INET follows `cidr`. JSON columns are embedded as
+// documents rather than strings.
+impl Serialize for Value<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Value::Null => serializer.serialize_none(),
+            Value::I8(v) => serializer.serialize_i8(v),
+            Value::I16(v) => serializer.serialize_i16(v),
+            Value::I32(v) => serializer.serialize_i32(v),
+            Value::I64(v) => serializer.serialize_i64(v),
+            Value::U8(v) => serializer.serialize_u8(v),
+            Value::U16(v) => serializer.serialize_u16(v),
+            Value::U32(v) => serializer.serialize_u32(v),
+            Value::U64(v) => serializer.serialize_u64(v),
+            Value::F32(v) => serializer.serialize_f32(v),
+            Value::F64(v) => serializer.serialize_f64(v),
+            Value::Timestamp(v) if serializer.is_human_readable() => {
+                serializer.serialize_str(&to_rfc3339(v))
+            }
+            Value::Timestamp(v) => serializer.serialize_i64(v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Binary(v) => serializer.serialize_bytes(v),
+            Value::Bool(v) => serializer.serialize_bool(v),
+            Value::Inet(v) => v.serialize(serializer),
+            Value::Mac(v) => v.serialize(serializer),
+            Value::Json(v) => serde_json::from_str::<serde_json::Value>(v)
+                .map_err(S::Error::custom)?
+                .serialize(serializer),
+        }
+    }
+}
+
+/// A [`Value`] that owns its data, so it outlives the [`Query`] it was read
+/// from and can be sent to other threads. See [`Row::to_owned`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedValue {
+    Null,
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    /// Microseconds since the Unix epoch, UTC.
+    Timestamp(i64),
+    String(String),
+    Binary(Vec<u8>),
+    Bool(bool),
+    Inet(IpInet),
+    Mac(MacAddress),
+    Json(String),
+}
+
+impl OwnedValue {
+    /// Borrows the value, to read it with [`FromValue`] or serde.
+    pub fn as_value(&self) -> Value<'_> {
+        match self {
+            OwnedValue::Null => Value::Null,
+            OwnedValue::I8(v) => Value::I8(*v),
+            OwnedValue::I16(v) => Value::I16(*v),
+            OwnedValue::I32(v) => Value::I32(*v),
+            OwnedValue::I64(v) => Value::I64(*v),
+            OwnedValue::U8(v) => Value::U8(*v),
+            OwnedValue::U16(v) => Value::U16(*v),
+            OwnedValue::U32(v) => Value::U32(*v),
+            OwnedValue::U64(v) => Value::U64(*v),
+            OwnedValue::F32(v) => Value::F32(*v),
+            OwnedValue::F64(v) => Value::F64(*v),
+            OwnedValue::Timestamp(v) => Value::Timestamp(*v),
+            OwnedValue::String(v) => Value::String(v),
+            OwnedValue::Binary(v) => Value::Binary(v),
+            OwnedValue::Bool(v) => Value::Bool(*v),
+            OwnedValue::Inet(v) => Value::Inet(*v),
+            OwnedValue::Mac(v) => Value::Mac(*v),
+            OwnedValue::Json(v) => Value::Json(v),
         }
     }
 }
 
-impl Value<'_> {
+impl Display for OwnedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_value().fmt(f)
+    }
+}
+
+impl Serialize for OwnedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_value().serialize(serializer)
+    }
+}
+
+impl From<Value<'_>> for OwnedValue {
+    fn from(value: Value<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Copies the value out of the result buffer.
+    pub fn into_owned(self) -> OwnedValue {
+        match self {
+            Value::Null => OwnedValue::Null,
+            Value::I8(v) => OwnedValue::I8(v),
+            Value::I16(v) => OwnedValue::I16(v),
+            Value::I32(v) => OwnedValue::I32(v),
+            Value::I64(v) => OwnedValue::I64(v),
+            Value::U8(v) => OwnedValue::U8(v),
+            Value::U16(v) => OwnedValue::U16(v),
+            Value::U32(v) => OwnedValue::U32(v),
+            Value::U64(v) => OwnedValue::U64(v),
+            Value::F32(v) => OwnedValue::F32(v),
+            Value::F64(v) => OwnedValue::F64(v),
+            Value::Timestamp(v) => OwnedValue::Timestamp(v),
+            Value::String(v) => OwnedValue::String(v.to_string()),
+            Value::Binary(v) => OwnedValue::Binary(v.to_vec()),
+            Value::Bool(v) => OwnedValue::Bool(v),
+            Value::Inet(v) => OwnedValue::Inet(v),
+            Value::Mac(v) => OwnedValue::Mac(v),
+            Value::Json(v) => OwnedValue::Json(v.to_string()),
+        }
+    }
+
+    /// Returns the bytes of a string, JSON or binary value.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match *self {
+            Value::String(v) | Value::Json(v) => Some(v.as_bytes()),
+            Value::Binary(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a string, JSON or binary value, replacing invalid
+    /// UTF-8 with `U+FFFD`. Text columns that are not valid UTF-8 are read as
+    /// [`Value::Binary`], this recovers them as strings.
+    pub fn to_str_lossy(&self) -> Option<Cow<'a, str>> {
+        self.as_bytes().map(String::from_utf8_lossy)
+    }
+
     pub(crate) unsafe fn from_ptr(
         res: *mut xdb_res_t,
         row: *mut xdb_row_t,
         i: u16,
         t: DataType,
-    ) -> Self {
+    ) -> Result<Self> {
         if xdb_column_null(res, row, i) {
-            return Self::Null;
+            return Ok(Self::Null);
         }
-        match t {
+        let value = match t {
             DataType::Null => Self::Null,
-            DataType::TinyInt | DataType::SmallInt | DataType::Int => {
-                Self::I32(xdb_column_int(res, row, i))
-            }
+            DataType::TinyInt => Self::I8(narrow(xdb_column_int(res, row, i), t)?),
+            DataType::SmallInt => Self::I16(narrow(xdb_column_int(res, row, i), t)?),
+            DataType::Int => Self::I32(xdb_column_int(res, row, i)),
             DataType::BigInt => Self::I64(xdb_column_int64(res, row, i)),
-            DataType::UTinyInt | DataType::USmallInt | DataType::UInt => {
-                Self::U32(xdb_column_uint(res, row, i))
-            }
+            DataType::UTinyInt => Self::U8(narrow(xdb_column_uint(res, row, i), t)?),
+            DataType::USmallInt => Self::U16(narrow(xdb_column_uint(res, row, i), t)?),
+            DataType::UInt => Self::U32(xdb_column_uint(res, row, i)),
             DataType::UBigInt => Self::U64(xdb_column_uint64(res, row, i)),
             DataType::Float => Self::F32(xdb_column_float(res, row, i)),
             DataType::Double => Self::F64(xdb_column_double(res, row, i)),
             DataType::Timestamp => Self::Timestamp(xdb_column_int64(res, row, i)),
-            DataType::Char | DataType::VChar => {
-                let ptr = xdb_column_str(res, row, i);
-                let str = CStr::from_ptr(ptr).to_str().unwrap();
-                Self::String(str)
+            DataType::Char | DataType::VChar | DataType::Json => {
+                let mut len = 0_i32;
+                let ptr = xdb_column_str2(res, row, i, &mut len);
+                let data = bytes(ptr as _, len)?;
+                match std::str::from_utf8(data) {
+                    Ok(str) if t == DataType::Json => Self::Json(str),
+                    Ok(str) => Self::String(str),
+                    Err(_) => Self::Binary(data),
+                }
             }
             DataType::Binary | DataType::VBinary => {
                 let mut len = 0_i32;
                 let ptr = xdb_column_blob(res, row, i, &mut len);
-                if len <= 0 {
-                    return Self::Null;
-                }
-                let data = from_raw_parts(ptr as *const u8, len as usize);
-                Self::Binary(data)
+                Self::Binary(bytes(ptr, len)?)
             }
             DataType::Bool => Self::Bool(xdb_column_bool(res, row, i)),
             DataType::Inet => {
-                let inet = *xdb_column_inet(res, row, i);
+                let inet = xdb_column_inet(res, row, i)
+                    .as_ref()
+                    .ok_or_else(|| Error::Decode("null INET value".into()))?;
+                let invalid = |e| Error::Decode(format!("invalid INET value: {e}"));
                 match inet.family {
                     4 => {
                         let mut buf = [0; 4];
                         buf.copy_from_slice(&inet.addr[0..4]);
-                        let net = Ipv4Inet::new(Ipv4Addr::from(buf), inet.mask).unwrap();
+                        let net = Ipv4Inet::new(Ipv4Addr::from(buf), inet.mask).map_err(invalid)?;
                         Self::Inet(IpInet::V4(net))
                     }
                     6 => {
-                        let net = Ipv6Inet::new(Ipv6Addr::from(inet.addr), inet.mask).unwrap();
+                        let net =
+                            Ipv6Inet::new(Ipv6Addr::from(inet.addr), inet.mask).map_err(invalid)?;
                         Self::Inet(IpInet::V6(net))
                     }
-                    _ => unreachable!(),
+                    family => {
+                        return Err(Error::Decode(format!(
+                            "invalid INET value: unknown address family {family}"
+                        )))
+                    }
                 }
             }
             DataType::Mac => {
-                let mac = *xdb_column_mac(res, row, i);
-                let address = MacAddress::new(mac.addr);
-                Self::Mac(address)
+                let mac = xdb_column_mac(res, row, i)
+                    .as_ref()
+                    .ok_or_else(|| Error::Decode("null MAC value".into()))?;
+                Self::Mac(MacAddress::new(mac.addr))
+            }
+            DataType::Array | DataType::Max => {
+                return Err(Error::Decode(format!("unsupported column type {t}")))
             }
-            DataType::Json => todo!(),
-            DataType::Array => todo!(),
-            DataType::Max => todo!(),
+        };
+        Ok(value)
+    }
+}
+
+// Borrows a buffer returned by the engine, which may be null when empty.
+unsafe fn bytes<'a>(ptr: *const c_void, len: i32) -> Result<&'a [u8]> {
+    match len {
+        0 => Ok(&[]),
+        len if len < 0 => Err(Error::Decode(format!("invalid value length {len}"))),
+        _ if ptr.is_null() => Err(Error::Decode("null value buffer".into())),
+        len => Ok(from_raw_parts(ptr as *const u8, len as usize)),
+    }
+}
+
+// The engine reads narrow integer columns through its `int` accessors.
+fn narrow<T: TryFrom<V>, V: Copy + Display>(v: V, t: DataType) -> Result<T> {
+    T::try_from(v).map_err(|_| Error::Decode(format!("{t} value {v} out of range")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr::null;
+
+    #[test]
+    fn test_bytes() {
+        unsafe {
+            assert_eq!(bytes(null(), 0).unwrap(), &[] as &[u8]);
+            assert!(matches!(bytes(null(), -1), Err(Error::Decode(_))));
+            assert!(matches!(bytes(null(), 1), Err(Error::Decode(_))));
+            let data = [1_u8, 2];
+            assert_eq!(bytes(data.as_ptr() as _, 2).unwrap(), &data);
+        }
+    }
+
+    #[test]
+    fn test_to_str_lossy() {
+        assert_eq!(Value::String("Alex").to_str_lossy().unwrap(), "Alex");
+        assert_eq!(
+            Value::Binary(b"A\xffx").to_str_lossy().unwrap(),
+            "A\u{fffd}x"
+        );
+        assert_eq!(Value::Binary(b"A\xffx").as_bytes(), Some(&b"A\xffx"[..]));
+        assert_eq!(Value::I32(1).to_str_lossy(), None);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let values = [
+            Value::Null,
+            Value::I64(-1),
+            Value::String("Alex"),
+            Value::Binary(&[0, 255]),
+            Value::Json(r#"{"a":1}"#),
+        ];
+        for v in values {
+            let owned = v.into_owned();
+            assert_eq!(owned.as_value(), v);
+            assert_eq!(owned.to_string(), v.to_string());
+            assert_eq!(owned.to_param().unwrap(), v.to_param().unwrap());
+        }
+        assert_eq!(
+            OwnedValue::from(Value::String("Alex")),
+            OwnedValue::String("Alex".into())
+        );
+        assert_eq!(
+            OwnedValue::from_value(&Value::U32(7)).unwrap(),
+            OwnedValue::U32(7)
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        use serde_json::{json, to_value};
+
+        let inet: IpInet = "192.168.1.10/24".parse().unwrap();
+        let mac: MacAddress = "00:1a:2b:3c:4d:5e".parse().unwrap();
+        let cases = [
+            (Value::Null, json!(null)),
+            (Value::I32(-1), json!(-1)),
+            (Value::U64(u64::MAX), json!(u64::MAX)),
+            (Value::F64(1.5), json!(1.5)),
+            (
+                Value::Timestamp(1_500_000),
+                json!("1970-01-01T00:00:01.500000Z"),
+            ),
+            (Value::String("Alex"), json!("Alex")),
+            (Value::Binary(&[0, 255]), json!([0, 255])),
+            (Value::Bool(true), json!(true)),
+            (Value::Inet(inet), json!("192.168.1.10/24")),
+            (Value::Mac(mac), json!("00:1A:2B:3C:4D:5E")),
+            (Value::Json(r#"{"a":[1]}"#), json!({"a": [1]})),
+        ];
+        for (v, expected) in cases {
+            assert_eq!(to_value(v).unwrap(), expected);
+            assert_eq!(to_value(v.into_owned()).unwrap(), expected);
         }
+        assert!(to_value(Value::Json("{")).is_err());
+    }
+
+    #[test]
+    fn test_serialize_compact() {
+        use serde::de::{value::Error as DeError, IntoDeserializer};
+        use serde_test::{assert_ser_tokens, Configure, Token};
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        // TIMESTAMP is its microseconds rather than RFC 3339 text.
+        let v = Value::Timestamp(1_500_000);
+        assert_ser_tokens(&v.compact(), &[Token::I64(1_500_000)]);
+        assert_ser_tokens(&v.into_owned().compact(), &[Token::I64(1_500_000)]);
+        assert_ser_tokens(&v.readable(), &[Token::Str("1970-01-01T00:00:01.500000Z")]);
+        assert_ser_tokens(&Value::String("Alex").compact(), &[Token::Str("Alex")]);
+
+        // And reads back as a date-time.
+        let de = IntoDeserializer::<DeError>::into_deserializer(1_500_000_i64);
+        let time: SystemTime = crate::timestamp::deserialize(de).unwrap();
+        assert_eq!(time, UNIX_EPOCH + Duration::from_micros(1_500_000));
+    }
+
+    #[test]
+    fn test_narrow() {
+        assert_eq!(narrow::<i8, _>(-128, DataType::TinyInt).unwrap(), -128);
+        assert_eq!(
+            narrow::<u16, _>(65535_u32, DataType::USmallInt).unwrap(),
+            65535
+        );
+        assert_eq!(
+            narrow::<i16, _>(40000, DataType::SmallInt)
+                .unwrap_err()
+                .to_string(),
+            Error::Decode("SMALLINT value 40000 out of range".into()).to_string()
+        );
+        assert!(narrow::<u8, _>(256_u32, DataType::UTinyInt).is_err());
+        assert_eq!(Value::I8(-1).to_param().unwrap(), ParamValue::Int(-1));
+        assert_eq!(Value::U16(7).into_owned(), OwnedValue::U16(7));
     }
 }